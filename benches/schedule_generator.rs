@@ -0,0 +1,142 @@
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use chrono::Weekday;
+use common::RecordBuilder;
+use criterion::{Criterion, criterion_group, criterion_main};
+use cryn_rs::{
+    models::CourseRecordType,
+    services::{CourseManager, ScheduleProblem},
+};
+use std::hint::black_box;
+
+const COURSE_COUNT: usize = 10;
+const LECTURE_GROUPS: i32 = 4;
+const TUTORIAL_GROUPS: i32 = 8;
+
+/// Same cap as the timetable view
+const MAX_SCHEDULES: usize = 1000;
+
+const DAYS: [Weekday; 6] = [
+    Weekday::Sat,
+    Weekday::Sun,
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+];
+
+/// 1h50 periods starting every 2 hours from 8:00
+const PERIOD_STARTS: [u32; 6] = [8, 10, 12, 14, 16, 18];
+
+/// Big faculty sized catalogue, groups spread over the week by a fixed seed
+fn synthetic_catalogue() -> CourseManager {
+    let mut course_manager = CourseManager::new();
+    let mut seed: u64 = 0x5eed;
+    let mut next_random = |bound: usize| {
+        // Plain LCG, good enough to scatter groups
+        seed = seed
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (seed >> 33) as usize % bound
+    };
+
+    for course_idx in 0..COURSE_COUNT {
+        let code = format!("SYN{:03}", course_idx + 101);
+        let groups = (1..=LECTURE_GROUPS)
+            .map(|group| (CourseRecordType::Lecture, group))
+            .chain((1..=TUTORIAL_GROUPS).map(|group| (CourseRecordType::Tutorial, group)));
+
+        for (record_type, group) in groups {
+            let start_hour = PERIOD_STARTS[next_random(PERIOD_STARTS.len())];
+            RecordBuilder::new(&code)
+                .group(group)
+                .record_type(record_type)
+                .day(DAYS[next_random(DAYS.len())])
+                .period(start_hour)
+                .seats(60, 0, 0)
+                .add_to(&mut course_manager);
+        }
+    }
+
+    course_manager
+}
+
+/// One more course than there are periods, every group of every course in those same periods
+/// No schedule exists but nothing clashes until the last course, so the search has to try everything
+fn pigeonhole_catalogue() -> CourseManager {
+    let periods: Vec<(Weekday, u32)> = DAYS[..2]
+        .iter()
+        .flat_map(|&day| PERIOD_STARTS[..4].iter().map(move |&hour| (day, hour)))
+        .collect();
+
+    let mut course_manager = CourseManager::new();
+    for course_idx in 0..=periods.len() {
+        let code = format!("PIG{:03}", course_idx + 101);
+        for (group, &(day, hour)) in (1..).zip(&periods) {
+            RecordBuilder::new(&code)
+                .group(group)
+                .day(day)
+                .period(hour)
+                .add_to(&mut course_manager);
+        }
+    }
+
+    course_manager
+}
+
+fn bench_generate(c: &mut Criterion) {
+    let course_manager = synthetic_catalogue();
+    let problem = ScheduleProblem::from_course_manager(&course_manager, Default::default(), &[]);
+
+    // Has to stay well under a frame or two to feel interactive
+    c.bench_function("generate capped schedules", |b| {
+        b.iter(|| black_box(&problem).generate(black_box(MAX_SCHEDULES)))
+    });
+
+    c.bench_function("generate 50k schedules", |b| {
+        b.iter(|| black_box(&problem).generate(black_box(50_000)))
+    });
+
+    let infeasible =
+        ScheduleProblem::from_course_manager(&pigeonhole_catalogue(), Default::default(), &[]);
+    assert!(infeasible.generate(1).is_empty());
+
+    // Worst case, the whole tree gets pruned without finding anything
+    c.bench_function("exhaust infeasible catalogue", |b| {
+        b.iter(|| black_box(&infeasible).generate(black_box(MAX_SCHEDULES)))
+    });
+
+    c.bench_function("build problem", |b| {
+        b.iter(|| {
+            ScheduleProblem::from_course_manager(
+                black_box(&course_manager),
+                Default::default(),
+                &[],
+            )
+        })
+    });
+}
+
+fn bench_overlaps(c: &mut Criterion) {
+    let problem =
+        ScheduleProblem::from_course_manager(&synthetic_catalogue(), Default::default(), &[]);
+    let bundles: Vec<_> = problem
+        .slots
+        .iter()
+        .flat_map(|slot| &slot.bundles)
+        .collect();
+
+    c.bench_function("every bundle pair overlap", |b| {
+        b.iter(|| {
+            bundles
+                .iter()
+                .flat_map(|a| bundles.iter().map(move |b| a.overlaps(b)))
+                .filter(|&overlaps| overlaps)
+                .count()
+        })
+    });
+}
+
+criterion_group!(benches, bench_generate, bench_overlaps);
+criterion_main!(benches);
@@ -1,8 +1,126 @@
-use crate::{services::CourseManager, utils, windows::MainWindow};
+use crate::{
+    models::{SavedGroup, SavedSession, SchedulePreferences, Settings},
+    services::{
+        CourseManager, ParseError, ParseInput, ParseOptions, ParserRegistry, SnapshotDiff,
+        SpreadsheetParser, diff_snapshots, is_same_dataset, session_store,
+    },
+    utils::{self, PendingFile, PickedFile},
+    windows::MainWindow,
+};
 use std::{cell::RefCell, rc::Rc, sync::Arc};
 
 pub struct CrynContext {
     pub course_manager: Rc<RefCell<CourseManager>>,
+    pub schedule_preferences: Rc<RefCell<SchedulePreferences>>,
+
+    /// File picked from the open dialog, loaded once it arrives
+    pub pending_file: Rc<RefCell<Option<PendingFile>>>,
+
+    /// Groups of the schedule picked in the time table, empty shows every group
+    pub chosen_groups: Rc<RefCell<Vec<SavedGroup>>>,
+
+    /// Persisted state, written back by eframe
+    pub saved_session: Rc<RefCell<SavedSession>>,
+
+    pub settings: Rc<RefCell<Settings>>,
+
+    /// Set by the screenshot button, the time table captures itself and clears it
+    pub screenshot_requested: Rc<RefCell<bool>>,
+
+    /// Every format we can read, detected per input
+    pub parser_registry: Rc<ParserRegistry>,
+
+    /// What changed between the previous import and the loaded one
+    pub snapshot_diff: Rc<RefCell<Option<SnapshotDiff>>>,
+}
+
+impl CrynContext {
+    pub fn parse_options(&self) -> ParseOptions {
+        ParseOptions {
+            afternoon_threshold_hour: self.settings.borrow().afternoon_threshold_hour,
+            spreadsheet_mapping: None,
+        }
+    }
+
+    /// Parses into a detached manager with whichever parser fits
+    pub fn stage_courses(&self, input: &ParseInput) -> Result<CourseManager, ParseError> {
+        CourseManager::stage_courses(&self.parser_registry, input, &self.parse_options())
+    }
+
+    /// Id of the parser that fits best
+    pub fn detect_parser(&self, input: &ParseInput) -> Option<&'static str> {
+        self.parser_registry.detect(input).map(|parser| parser.id())
+    }
+
+    /// Parses the loaded source again with the current settings
+    pub fn reparse_courses(&self) -> Result<(), ParseError> {
+        let (staged, source_name) = {
+            let course_manager = self.course_manager.borrow();
+            if course_manager.source_data.is_empty() {
+                return Ok(());
+            }
+
+            (
+                course_manager.restage(&self.parser_registry, &self.parse_options())?,
+                course_manager.source_name.clone(),
+            )
+        };
+
+        self.replace_courses(staged, &source_name);
+
+        Ok(())
+    }
+
+    /// Swaps in freshly parsed courses, picks are remembered per dataset
+    pub fn replace_courses(&self, staged: CourseManager, source_name: &str) {
+        self.stash_selection();
+
+        {
+            let mut course_manager = self.course_manager.borrow_mut();
+
+            // Re-parses of the same data arent a new snapshot, another term isnt one either
+            if course_manager.source_data != staged.source_data {
+                let is_newer_snapshot = !course_manager.course_records.is_empty()
+                    && is_same_dataset(&course_manager, &staged);
+                *self.snapshot_diff.borrow_mut() =
+                    is_newer_snapshot.then(|| diff_snapshots(&course_manager, &staged));
+            }
+
+            course_manager.commit_staged(staged);
+            course_manager.source_name = source_name.to_owned();
+        }
+
+        self.restore_selection();
+    }
+
+    /// Saves the picks made against the current dataset into the session
+    pub fn stash_selection(&self) {
+        let course_manager = self.course_manager.borrow();
+        if course_manager.course_records.is_empty() {
+            return;
+        }
+
+        let selection =
+            session_store::capture_selection(&course_manager, &self.chosen_groups.borrow());
+        self.saved_session
+            .borrow_mut()
+            .insert_selection(session_store::dataset_hash(&course_manager), selection);
+    }
+
+    fn restore_selection(&self) {
+        let course_manager = self.course_manager.borrow();
+        let dataset_hash = session_store::dataset_hash(&course_manager);
+
+        match self.saved_session.borrow().selections.get(&dataset_hash) {
+            Some(selection) => {
+                session_store::apply_selection(&course_manager, selection);
+                *self.chosen_groups.borrow_mut() = selection.chosen_groups.clone();
+            }
+
+            // New term, start clean
+            None => self.chosen_groups.borrow_mut().clear(),
+        }
+    }
 }
 
 pub struct CrynApp {
@@ -12,6 +130,9 @@ pub struct CrynApp {
     /* Whatever */
     _course_manager: Rc<RefCell<CourseManager>>,
     context: CrynContext,
+
+    /// One per file that failed to load, shown in a modal until dismissed
+    load_errors: Vec<String>,
 }
 
 impl CrynApp {
@@ -32,15 +153,39 @@ impl CrynApp {
             style.interaction.selectable_labels = false;
         });
 
-        let course_manager = Self::initialize_course_manager(); /* Original ref */
+        // Restore last session
+        let saved_session = session_store::load_session(cc.storage);
+        saved_session.settings.apply(&cc.egui_ctx);
+
+        let course_manager = Rc::new(RefCell::new(CourseManager::new())); /* Original ref */
         let app_ctx = CrynContext {
             course_manager: Rc::clone(&course_manager),
+            schedule_preferences: Rc::new(RefCell::new(saved_session.preferences.clone())),
+            pending_file: Rc::new(RefCell::new(None)),
+            chosen_groups: Rc::new(RefCell::new(Vec::new())),
+            settings: Rc::new(RefCell::new(saved_session.settings.clone())),
+            screenshot_requested: Rc::new(RefCell::new(false)),
+            parser_registry: Rc::new(ParserRegistry::with_builtin_parsers()),
+            snapshot_diff: Rc::new(RefCell::new(None)),
+            saved_session: Rc::new(RefCell::new(saved_session)),
         };
 
+        Self::initialize_courses(&app_ctx);
+
+        let mut main_window = MainWindow::new(&app_ctx);
+        let startup_view = match app_ctx.settings.borrow().startup_view.view_name() {
+            Some(view_name) => Some(view_name.to_owned()),
+            None => app_ctx.saved_session.borrow().current_view.clone(),
+        };
+        if let Some(startup_view) = startup_view {
+            main_window.switch_to_view_by_name(&startup_view, &app_ctx);
+        }
+
         Self {
-            main_window: MainWindow::new(&app_ctx),
+            main_window,
             _course_manager: course_manager,
             context: app_ctx,
+            load_errors: Vec::new(),
         }
     }
 
@@ -74,10 +219,45 @@ impl CrynApp {
         cc.egui_ctx.set_fonts(fonts);
     }
 
-    fn initialize_course_manager() -> Rc<RefCell<CourseManager>> {
-        let mut course_manager = CourseManager::new();
-        let data = include_str!("../assets/data/sample_courses.txt");
-        course_manager.parse_courses(data);
+    fn initialize_courses(app_ctx: &CrynContext) {
+        // Desktop can be handed a file to open
+        #[cfg(not(target_arch = "wasm32"))]
+        let arg_file = std::env::args()
+            .nth(1)
+            .and_then(|path| utils::read_file(std::path::Path::new(&path)));
+
+        #[cfg(target_arch = "wasm32")]
+        let arg_file: Option<PickedFile> = None;
+
+        // Spreadsheets go through the mapping dialog once the window is up
+        let arg_file = arg_file.and_then(|file| {
+            if !Self::is_spreadsheet(app_ctx, &file) {
+                return Some(file);
+            }
+
+            *app_ctx.pending_file.borrow_mut() = Some(Rc::new(RefCell::new(Some(file))));
+            None
+        });
+
+        let loaded = arg_file.is_some_and(|file| {
+            Self::load_courses_into(app_ctx, &file)
+                .inspect_err(|err| utils::log(err))
+                .is_ok()
+        });
+
+        // Fallback to the bundled sample
+        if !loaded {
+            let data = include_str!("../assets/data/sample_courses.txt");
+            if let Err(err) = Self::load_courses_into(
+                app_ctx,
+                &PickedFile {
+                    name: "sample_courses.txt".to_owned(),
+                    bytes: data.as_bytes().to_vec(),
+                },
+            ) {
+                utils::log(&err);
+            }
+        }
 
         // utils::log(
         //     format!(
@@ -90,15 +270,133 @@ impl CrynApp {
         //     )
         //     .as_str(),
         // );
+    }
+
+    fn load_courses_into(app_ctx: &CrynContext, file: &PickedFile) -> Result<(), String> {
+        match app_ctx.stage_courses(&ParseInput::new(&file.name, &file.bytes)) {
+            Ok(staged) => {
+                utils::log(&format!(
+                    "{} ({}): {}",
+                    file.name, staged.parser_id, staged.parse_report
+                ));
+                staged
+                    .parse_report
+                    .rejected
+                    .iter()
+                    .for_each(|row| utils::log(&format!("Skipped {}", row)));
+
+                app_ctx.replace_courses(staged, &file.name);
+                Ok(())
+            }
+            Err(err) => Err(format!("Failed to load {}: {}", file.name, err)),
+        }
+    }
+
+    /// Spreadsheets go through the column mapping dialog instead
+    fn is_spreadsheet(app_ctx: &CrynContext, file: &PickedFile) -> bool {
+        app_ctx.detect_parser(&ParseInput::new(&file.name, &file.bytes))
+            == Some(SpreadsheetParser::ID)
+    }
+
+    /// Loads picked or dropped files, the last good one wins and every failure gets reported
+    fn handle_incoming_files(&mut self, ctx: &egui::Context) {
+        let mut files = utils::take_dropped_files(ctx);
+
+        let picked_file = self
+            .context
+            .pending_file
+            .borrow()
+            .as_ref()
+            .and_then(|pending| pending.borrow_mut().take());
+        if let Some(picked_file) = picked_file {
+            self.context.pending_file.borrow_mut().take();
+            files.push(picked_file);
+        }
+
+        if files.is_empty() {
+            return;
+        }
+
+        for file in files {
+            let loaded = if Self::is_spreadsheet(&self.context, &file) {
+                self.main_window
+                    .open_spreadsheet_dialog(&file, &self.context)
+            } else {
+                Self::load_courses_into(&self.context, &file)
+            };
 
-        Rc::new(RefCell::new(course_manager))
+            if let Err(err) = loaded {
+                self.load_errors.push(err);
+            }
+        }
+
+        // Views cache course data
+        self.main_window.refresh_current_view(&self.context);
+    }
+
+    /// Saves the time table capture once it comes back from the renderer
+    fn handle_screenshot(&mut self, ctx: &egui::Context) {
+        let Some(image) = utils::take_screenshot(ctx) else {
+            return;
+        };
+
+        let saved = utils::encode_png(&image)
+            .map_err(|err| format!("Failed to encode screenshot: {}", err))
+            .and_then(|bytes| utils::save_file("timetable.png", "PNG image", &["png"], &bytes));
+        if let Err(err) = saved {
+            utils::log(&err);
+        }
+    }
+
+    fn render_load_errors(&mut self, ctx: &egui::Context) {
+        if self.load_errors.is_empty() {
+            return;
+        }
+
+        let mut dismissed = false;
+        egui::Modal::new(egui::Id::new("load_error_modal")).show(ctx, |ui| {
+            if self.load_errors.len() == 1 {
+                ui.heading("Couldn't open file");
+            } else {
+                ui.heading(format!("Couldn't open {} files", self.load_errors.len()));
+            }
+
+            for load_error in &self.load_errors {
+                ui.label(load_error);
+            }
+            ui.add_space(8.0);
+
+            if ui.button("OK").clicked() {
+                dismissed = true;
+            }
+        });
+
+        if dismissed {
+            self.load_errors.clear();
+        }
     }
 }
 
 // App render loop
 impl eframe::App for CrynApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // Picked/dropped course files
+        self.handle_incoming_files(ctx);
+        self.handle_screenshot(ctx);
+
         // Render main window
         self.main_window.render(ctx, &self.context);
+        self.render_load_errors(ctx);
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        self.context.stash_selection();
+
+        let mut saved_session = self.context.saved_session.borrow_mut();
+        saved_session.preferences = self.context.schedule_preferences.borrow().clone();
+        saved_session.settings = self.context.settings.borrow().clone();
+        saved_session.current_view = self.main_window.current_view_name();
+
+        session_store::save_session(storage, &saved_session);
     }
 }
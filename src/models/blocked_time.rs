@@ -0,0 +1,50 @@
+use super::{OrderedWeekday, TimeFormat};
+use chrono::{NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// Personal busy time, ie a part time job or Friday prayer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockedTime {
+    pub day: Weekday,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub label: String,
+
+    /// Hard blocks rule groups out, soft ones only make schedules rank lower
+    pub hard: bool,
+}
+
+impl BlockedTime {
+    pub fn new(day: Weekday, start_time: NaiveTime, end_time: NaiveTime) -> Self {
+        Self {
+            day,
+            start_time,
+            end_time,
+            label: "Busy".to_owned(),
+            hard: true,
+        }
+    }
+
+    /// Minutes of [start_time, end_time) on `day` that fall inside this block
+    pub fn overlap_minutes(&self, day: Weekday, start_time: NaiveTime, end_time: NaiveTime) -> i64 {
+        if day != self.day {
+            return 0;
+        }
+
+        (end_time.min(self.end_time) - start_time.max(self.start_time))
+            .num_minutes()
+            .max(0)
+    }
+
+    /// Label, when and how hard, ie "Work (Sunday 09:00 - 12:00, hard)"
+    pub fn describe(&self, time_format: TimeFormat) -> String {
+        format!(
+            "{} ({} {} - {}, {})",
+            self.label,
+            OrderedWeekday::from(self.day),
+            time_format.format(self.start_time),
+            time_format.format(self.end_time),
+            if self.hard { "hard" } else { "soft" }
+        )
+    }
+}
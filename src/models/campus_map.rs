@@ -0,0 +1,77 @@
+use super::Location;
+use serde::{Deserialize, Serialize};
+
+const BUNDLED_CAMPUS_MAP: &str = include_str!("../../assets/data/campus_map.json");
+
+/// Minutes needed to get between two places, either campus names or building numbers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Route {
+    pub between: [String; 2],
+    pub minutes: u32,
+}
+
+impl Route {
+    fn connects(&self, a: &str, b: &str) -> bool {
+        let [first, second] = &self.between;
+        (first == a && second == b) || (first == b && second == a)
+    }
+}
+
+/// How long it takes to walk/drive between places, shipped in `assets/data/campus_map.json`
+/// No serde default here, Default parses the bundled file and would recurse
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CampusMap {
+    /// Between two campuses without a route
+    pub campus_minutes: u32,
+
+    /// Between two buildings of the same campus without a route, 0 never warns
+    pub building_minutes: u32,
+    pub routes: Vec<Route>,
+}
+
+impl CampusMap {
+    pub fn bundled() -> Self {
+        serde_json::from_str(BUNDLED_CAMPUS_MAP).expect("bundled campus map is valid")
+    }
+
+    /// Minutes needed between the end of one session and the start of the next
+    /// Places we cant tell apart need none
+    pub fn travel_minutes(&self, from: &Location, to: &Location) -> u32 {
+        let buildings = different(&from.building, &to.building);
+
+        // Building routes are the most specific
+        if let Some(minutes) = buildings.and_then(|(a, b)| self.route_minutes(a, b)) {
+            return minutes;
+        }
+
+        if let Some((a, b)) = different(&from.campus, &to.campus) {
+            return self.route_minutes(a, b).unwrap_or(self.campus_minutes);
+        }
+
+        match buildings {
+            Some(_) => self.building_minutes,
+            None => 0,
+        }
+    }
+
+    fn route_minutes(&self, a: &str, b: &str) -> Option<u32> {
+        self.routes
+            .iter()
+            .find(|route| route.connects(a, b))
+            .map(|route| route.minutes)
+    }
+}
+
+/// Both known and not the same
+fn different<'a>(a: &'a Option<String>, b: &'a Option<String>) -> Option<(&'a str, &'a str)> {
+    match (a, b) {
+        (Some(a), Some(b)) if a != b => Some((a, b)),
+        _ => None,
+    }
+}
+
+impl Default for CampusMap {
+    fn default() -> Self {
+        Self::bundled()
+    }
+}
@@ -1,4 +1,6 @@
+use super::CourseRecordType;
 use bitflags::bitflags;
+use std::collections::BTreeMap;
 
 bitflags! {
     #[derive(Debug)]
@@ -36,6 +38,9 @@ pub struct CourseDefinition {
     pub flags: CourseFlags, // For ykyk ;) bas we're graduating 5alas :(
     pub selected: bool,
 
+    /// Groups the user locked per type, the generator only varies the rest
+    pub pinned_groups: BTreeMap<CourseRecordType, i32>,
+
     // To remove later?
     pub lecture_count: u32,
     pub tutorial_count: u32,
@@ -49,6 +54,13 @@ impl CourseDefinition {
             ..Default::default()
         }
     }
+
+    /// Unpinned types allow any group
+    pub fn allows_group(&self, record_type: CourseRecordType, group: i32) -> bool {
+        self.pinned_groups
+            .get(&record_type)
+            .is_none_or(|&pinned| pinned == group)
+    }
 }
 
 impl Default for CourseDefinition {
@@ -58,6 +70,7 @@ impl Default for CourseDefinition {
             name: "ammar wkda".to_owned(),
             flags: CourseFlags::None,
             selected: false,
+            pinned_groups: BTreeMap::new(),
             lecture_count: 0,
             tutorial_count: 0,
         }
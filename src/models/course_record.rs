@@ -1,9 +1,12 @@
-use super::CourseDefinition;
-use chrono::{NaiveTime, Weekday};
+use super::{Availability, CourseDefinition, Location, RecordStatus};
+use chrono::{NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
 use std::{cell::RefCell, rc::Rc};
 use strum::EnumString;
 
-#[derive(Debug, EnumString, PartialEq)]
+#[derive(
+    Debug, Clone, Copy, EnumString, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
 #[strum(ascii_case_insensitive)]
 pub enum CourseRecordType {
     None,
@@ -12,7 +15,7 @@ pub enum CourseRecordType {
 }
 
 /// Backwards compatibility
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, EnumString, PartialEq, Eq)]
 pub enum CourseParseFormat {
     Standard,
     IrregularWithoutNameGroupPrefixed,
@@ -23,6 +26,22 @@ pub enum CourseParseFormat {
     Excel,
 }
 
+/// Identifies a record across re-imports of the same term
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RecordKey {
+    /// Portal row id
+    Id(u32),
+
+    /// Sources without ids, a group only meets once per slot
+    Slot {
+        code: String,
+        record_type: CourseRecordType,
+        group: i32,
+        day: Weekday,
+        start_time: NaiveTime,
+    },
+}
+
 #[derive(Debug)]
 pub struct CourseRecord {
     pub course_definition: Rc<RefCell<CourseDefinition>>,
@@ -34,16 +53,23 @@ pub struct CourseRecord {
     pub class_size: i32,
     pub enrolled: i32,
     pub waiting: i32,
-    pub status: String,
-    pub location: String,
+    pub status: RecordStatus,
+    pub location: Location,
     pub parse_format: CourseParseFormat,
 
+    /// Row id from the portal, other sources may not have one
+    pub id: Option<u32>,
+
+    /// When the portal took the snapshot this record came from
+    pub snapshot_time: Option<NaiveDateTime>,
+
     // Mullec/Multut flags indices
     pub mullec_index: i32,
     pub multut_index: i32,
 }
 
 impl CourseRecord {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         course_definition: Rc<RefCell<CourseDefinition>>,
         group: i32,
@@ -54,8 +80,8 @@ impl CourseRecord {
         class_size: i32,
         enrolled: i32,
         waiting: i32,
-        status: String,
-        location: String,
+        status: RecordStatus,
+        location: Location,
         parse_format: CourseParseFormat,
     ) -> Self {
         Self {
@@ -71,8 +97,32 @@ impl CourseRecord {
             status,
             location,
             parse_format,
+            id: None,
+            snapshot_time: None,
             mullec_index: -1,
             multut_index: -1,
         }
     }
+
+    pub fn key(&self) -> RecordKey {
+        match self.id {
+            Some(id) => RecordKey::Id(id),
+            None => self.slot_key(),
+        }
+    }
+
+    pub fn availability(&self) -> Availability {
+        Availability::new(&self.status, self.class_size, self.enrolled, self.waiting)
+    }
+
+    /// Ignores the id, for comparing against sources that dont have one
+    pub fn slot_key(&self) -> RecordKey {
+        RecordKey::Slot {
+            code: self.course_definition.borrow().code.clone(),
+            record_type: self.record_type,
+            group: self.group,
+            day: self.day,
+            start_time: self.start_time,
+        }
+    }
 }
@@ -7,6 +7,9 @@ use std::{
     rc::Rc,
 };
 
+/// A record and the lane it was stacked in
+pub type LaneRecord = (Rc<RefCell<CourseRecord>>, usize);
+
 pub struct CourseSpan {
     map: HashMap<NaiveTime, Vec<Rc<RefCell<CourseRecord>>>>,
     min_from: Option<NaiveTime>,
@@ -29,9 +32,7 @@ impl CourseSpan {
             ..
         } = *record.borrow();
 
-        self.entry(start_time)
-            .or_insert(Vec::new())
-            .push(Rc::clone(record));
+        self.entry(start_time).or_default().push(Rc::clone(record));
 
         if self.min_from.is_none() || start_time < self.min_from.unwrap() {
             self.min_from = Some(start_time);
@@ -42,6 +43,51 @@ impl CourseSpan {
         }
     }
 
+    pub fn min_from(&self) -> Option<NaiveTime> {
+        self.min_from
+    }
+
+    pub fn max_to(&self) -> Option<NaiveTime> {
+        self.max_to
+    }
+
+    /// Puts each record in the first lane thats free by its start time
+    /// Overlapping records end up in different lanes, returns the lane count too
+    pub fn layout_lanes(&self) -> (Vec<LaneRecord>, usize) {
+        let mut records: Vec<Rc<RefCell<CourseRecord>>> =
+            self.map.values().flatten().cloned().collect();
+        records.sort_by_key(|record| {
+            let record = record.borrow();
+            (record.start_time, record.end_time)
+        });
+
+        let mut lane_ends: Vec<NaiveTime> = Vec::new();
+        let laid_out = records
+            .into_iter()
+            .map(|record_rc| {
+                let (start_time, end_time) = {
+                    let record = record_rc.borrow();
+                    (record.start_time, record.end_time)
+                };
+
+                let lane = match lane_ends.iter().position(|&end| end <= start_time) {
+                    Some(lane) => {
+                        lane_ends[lane] = end_time;
+                        lane
+                    }
+                    None => {
+                        lane_ends.push(end_time);
+                        lane_ends.len() - 1
+                    }
+                };
+
+                (record_rc, lane)
+            })
+            .collect();
+
+        (laid_out, lane_ends.len())
+    }
+
     pub fn get_period_count(&self) -> u32 {
         // 8->8:50 period
         // 9->9:50 period, etc
@@ -54,6 +100,12 @@ impl CourseSpan {
     }
 }
 
+impl Default for CourseSpan {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Deref for CourseSpan {
     type Target = HashMap<NaiveTime, Vec<Rc<RefCell<CourseRecord>>>>;
 
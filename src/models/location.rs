@@ -0,0 +1,109 @@
+use std::fmt;
+
+/// Where a group meets, portal locations look like `[20103]20103-60-الجيزة الرئيسي`
+/// Other sources usually just have a room name, that ends up in `room`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Location {
+    /// Id between the brackets, not always the room number ie `[204071]20407 A`
+    pub room_code: Option<String>,
+
+    /// Readable room name, `20407 A`, `Blue Room` etc
+    pub room: String,
+
+    /// Rooms are numbered building, floor then 2 digit room so 20103 is in building 20
+    pub building: Option<String>,
+    pub campus: Option<String>,
+
+    /// Seats in the room, not the class size
+    pub capacity: Option<u32>,
+
+    /// Exactly as imported, exports write this back
+    pub raw: String,
+}
+
+impl Location {
+    pub fn parse(raw: &str) -> Self {
+        // Groups without a room get dashes
+        let trimmed = raw.trim().trim_matches('-').trim();
+        let (room_code, rest) = match trimmed
+            .strip_prefix('[')
+            .and_then(|rest| rest.split_once(']'))
+        {
+            Some((room_code, rest)) => (Some(room_code.trim().to_owned()), rest),
+            None => (None, trimmed),
+        };
+
+        let mut room = rest;
+        let mut campus = None;
+        let mut capacity = None;
+
+        // Only portal locations are dash separated, dont split "Hall-5" from elsewhere
+        if room_code.is_some()
+            && let Some((head, last)) = rest.rsplit_once('-')
+        {
+            campus = Some(collapse_whitespace(last)).filter(|campus| !campus.is_empty());
+            room = head;
+
+            if let Some((head, seats)) = head.rsplit_once('-')
+                && let Ok(seats) = seats.trim().parse::<u32>()
+            {
+                capacity = Some(seats);
+                room = head;
+            }
+        }
+
+        let room = collapse_whitespace(room);
+        let building = building_of(&room).or_else(|| room_code.as_deref().and_then(building_of));
+
+        Self {
+            room_code,
+            room,
+            building,
+            campus,
+            capacity,
+            raw: raw.to_owned(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.room.is_empty() && self.room_code.is_none()
+    }
+
+    /// Short label for tight spaces, ie timetable blocks
+    pub fn room_label(&self) -> String {
+        match (&self.room, &self.room_code) {
+            (room, _) if room.bytes().all(|b| b.is_ascii_digit()) && !room.is_empty() => {
+                format!("Room {}", room)
+            }
+            (room, Some(room_code)) if room.is_empty() => format!("Room {}", room_code),
+            (room, _) => room.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.room_label())?;
+        if let Some(campus) = &self.campus {
+            write!(f, ", {}", campus)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Leading room number minus floor and room digits
+fn building_of(room: &str) -> Option<String> {
+    let digits: String = room.chars().take_while(|c| c.is_ascii_digit()).collect();
+    if digits.len() < 4 {
+        return None;
+    }
+
+    // 0 is what the portal uses for unassigned rooms
+    let building = digits[..digits.len() - 3].trim_start_matches('0');
+    (!building.is_empty()).then(|| building.to_owned())
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -1,9 +1,29 @@
+mod blocked_time;
+mod campus_map;
 mod course_definition;
 mod course_record;
 mod course_span;
+mod location;
 mod ordered_weekday;
+mod saved_session;
+mod schedule_preferences;
+mod seat_availability;
+mod semester;
+mod settings;
+mod time_format;
+mod week_occupancy;
 
+pub use blocked_time::*;
+pub use campus_map::*;
 pub use course_definition::*;
 pub use course_record::*;
 pub use course_span::*;
-pub use ordered_weekday::*;
\ No newline at end of file
+pub use location::*;
+pub use ordered_weekday::*;
+pub use saved_session::*;
+pub use schedule_preferences::*;
+pub use seat_availability::*;
+pub use semester::*;
+pub use settings::*;
+pub use time_format::*;
+pub use week_occupancy::*;
@@ -1,13 +1,30 @@
 use chrono::Weekday;
 
+/// Weekday usable as a map key, Saturday first like the portal
+/// The order is fixed, sort by `days_since` to show days from the configured week start
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct OrderedWeekday(Weekday);
 
+impl OrderedWeekday {
+    pub fn weekday(&self) -> Weekday {
+        self.0
+    }
+
+    /// Position in a week starting at `week_start`, 0 to 6
+    pub fn days_since(&self, week_start: Weekday) -> u32 {
+        self.0.days_since(week_start)
+    }
+
+    /// Days of the week starting at `week_start`
+    pub fn week(week_start: Weekday) -> impl Iterator<Item = Weekday> {
+        std::iter::successors(Some(week_start), |day| Some(day.succ())).take(7)
+    }
+}
+
 impl Ord for OrderedWeekday {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.0
-            .days_since(Weekday::Sat)
-            .cmp(&other.0.days_since(Weekday::Sat))
+        self.days_since(Weekday::Sat)
+            .cmp(&other.days_since(Weekday::Sat))
     }
 }
 
@@ -23,9 +40,9 @@ impl From<Weekday> for OrderedWeekday {
     }
 }
 
-impl ToString for OrderedWeekday {
-    fn to_string(&self) -> String {
-        match self.0 {
+impl std::fmt::Display for OrderedWeekday {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self.0 {
             Weekday::Sat => "Saturday",
             Weekday::Sun => "Sunday",
             Weekday::Mon => "Monday",
@@ -33,7 +50,8 @@ impl ToString for OrderedWeekday {
             Weekday::Wed => "Wednesday",
             Weekday::Thu => "Thursday",
             Weekday::Fri => "Friday",
-        }
-        .to_owned()
+        };
+
+        write!(f, "{}", name)
     }
 }
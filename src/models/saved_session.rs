@@ -0,0 +1,64 @@
+use super::{CourseRecordType, SchedulePreferences, Semester, Settings};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A group picked in the time table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedGroup {
+    pub code: String,
+    pub record_type: CourseRecordType,
+    pub group: i32,
+}
+
+/// Picks made against a single dataset
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SavedSelection {
+    pub selected_codes: Vec<String>,
+    pub chosen_groups: Vec<SavedGroup>,
+
+    /// Locked groups of the selected courses
+    #[serde(default)]
+    pub pinned_groups: Vec<SavedGroup>,
+
+    /// Unix timestamp, oldest selections get evicted first
+    pub last_used: i64,
+}
+
+/// Everything that outlives the app
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedSession {
+    /// Keyed by `CourseManager::dataset_hash`
+    pub selections: HashMap<String, SavedSelection>,
+    pub preferences: SchedulePreferences,
+    pub settings: Settings,
+
+    /// Bounds of exported calendars
+    pub semester: Semester,
+    pub current_view: Option<String>,
+}
+
+impl SavedSession {
+    pub const STORAGE_KEY: &str = "cryn_session";
+
+    /// How many datasets we remember picks for
+    pub const MAX_SELECTIONS: usize = 8;
+
+    pub fn insert_selection(&mut self, dataset_hash: String, selection: SavedSelection) {
+        self.selections.insert(dataset_hash, selection);
+
+        // Drop the stalest terms
+        while self.selections.len() > Self::MAX_SELECTIONS {
+            let Some(oldest) = self
+                .selections
+                .iter()
+                .min_by_key(|(_, selection)| selection.last_used)
+                .map(|(hash, _)| hash.clone())
+            else {
+                break;
+            };
+
+            self.selections.remove(&oldest);
+        }
+    }
+}
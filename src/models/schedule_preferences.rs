@@ -0,0 +1,51 @@
+use super::GroupFilter;
+use chrono::{NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// What makes a schedule good, every weight is a penalty multiplier
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SchedulePreferences {
+    /// Per day we have to show up on campus
+    pub campus_days_weight: f32,
+
+    /// Sessions starting before this are penalized
+    pub earliest_start: Option<NaiveTime>,
+    pub early_start_weight: f32,
+
+    /// Per idle hour between two sessions of the same day
+    pub gaps_weight: f32,
+
+    /// Per hour between the first start and last end of a day
+    pub compactness_weight: f32,
+
+    /// Day we would rather keep free
+    pub free_day: Option<Weekday>,
+    pub free_day_weight: f32,
+
+    /// Per break too short to get to the next session's campus or building
+    pub tight_transfer_weight: f32,
+
+    /// Per hour of class during soft blocked times
+    pub blocked_time_weight: f32,
+
+    /// Groups left out of generated schedules altogether
+    pub group_filter: GroupFilter,
+}
+
+impl Default for SchedulePreferences {
+    fn default() -> Self {
+        Self {
+            campus_days_weight: 10.0,
+            earliest_start: NaiveTime::from_hms_opt(9, 0, 0),
+            early_start_weight: 5.0,
+            gaps_weight: 2.0,
+            compactness_weight: 0.5,
+            free_day: None,
+            free_day_weight: 15.0,
+            tight_transfer_weight: 10.0,
+            blocked_time_weight: 10.0,
+            group_filter: GroupFilter::default(),
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Registration status as reported by the portal
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub enum RecordStatus {
+    Open,
+    Closed,
+
+    /// Anything else the portal came up with, kept verbatim
+    Other(String),
+
+    /// Blank
+    #[default]
+    Unknown,
+}
+
+impl RecordStatus {
+    /// Opened/Open/Closed in any case, never fails
+    pub fn parse(raw: &str) -> Self {
+        let raw = raw.trim();
+        let lowercase = raw.to_lowercase();
+
+        if lowercase.is_empty() {
+            RecordStatus::Unknown
+        } else if lowercase.starts_with("open") {
+            RecordStatus::Open
+        } else if lowercase.starts_with("close") {
+            RecordStatus::Closed
+        } else {
+            RecordStatus::Other(raw.to_owned())
+        }
+    }
+}
+
+impl fmt::Display for RecordStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordStatus::Open => write!(f, "Open"),
+            RecordStatus::Closed => write!(f, "Closed"),
+            RecordStatus::Other(status) => write!(f, "{}", status),
+            RecordStatus::Unknown => Ok(()),
+        }
+    }
+}
+
+/// Whether a group can still be registered, derived from status and seat counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Availability {
+    Open {
+        seats_left: i32,
+    },
+
+    /// No seats left, `waiting` people queue for one
+    Waitlisted {
+        waiting: i32,
+    },
+    Closed,
+
+    /// The portal often reports a class size of 0, seats cant be counted then
+    UnknownCapacity,
+}
+
+impl Availability {
+    pub fn new(status: &RecordStatus, class_size: i32, enrolled: i32, waiting: i32) -> Self {
+        if *status == RecordStatus::Closed {
+            return Availability::Closed;
+        }
+
+        if class_size <= 0 {
+            return Availability::UnknownCapacity;
+        }
+
+        match class_size - enrolled {
+            seats_left if seats_left > 0 => Availability::Open { seats_left },
+            _ => Availability::Waitlisted { waiting },
+        }
+    }
+
+    /// Groups with several records are as available as their least available one
+    pub fn worst(self, other: Availability) -> Availability {
+        if other.rank() > self.rank() {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Availability::Open { .. } => 0,
+            Availability::UnknownCapacity => 1,
+            Availability::Waitlisted { .. } => 2,
+            Availability::Closed => 3,
+        }
+    }
+}
+
+impl fmt::Display for Availability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Availability::Open { seats_left: 1 } => write!(f, "1 seat left"),
+            Availability::Open { seats_left } => write!(f, "{} seats left", seats_left),
+            Availability::Waitlisted { waiting: 0 } => write!(f, "Full"),
+            Availability::Waitlisted { waiting } => write!(f, "Full, {} waiting", waiting),
+            Availability::Closed => write!(f, "Closed"),
+            Availability::UnknownCapacity => write!(f, "Seats unknown"),
+        }
+    }
+}
+
+/// Which groups the schedule generator may pick
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GroupFilter {
+    pub skip_closed: bool,
+
+    /// Full and waitlisted groups
+    pub skip_full: bool,
+}
+
+impl GroupFilter {
+    pub fn allows(&self, availability: Availability) -> bool {
+        match availability {
+            Availability::Closed => !self.skip_closed,
+            Availability::Waitlisted { .. } => !self.skip_full,
+            Availability::Open { .. } | Availability::UnknownCapacity => true,
+        }
+    }
+}
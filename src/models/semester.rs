@@ -0,0 +1,30 @@
+use chrono::{Datelike, Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Typical term length, used until the user sets real dates
+const DEFAULT_SEMESTER_WEEKS: u64 = 15;
+
+/// First and last day of teaching, both inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Semester {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl Semester {
+    pub fn is_valid(&self) -> bool {
+        self.start <= self.end
+    }
+}
+
+impl Default for Semester {
+    fn default() -> Self {
+        let today = chrono::Local::now().date_naive();
+        let start = NaiveDate::from_ymd_opt(today.year(), today.month(), 1).unwrap_or(today);
+
+        Self {
+            start,
+            end: start + Days::new(DEFAULT_SEMESTER_WEEKS * 7),
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use super::{BlockedTime, CampusMap, TimeFormat};
+use chrono::Weekday;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Theme {
+    System,
+    Light,
+    Dark,
+}
+
+impl From<Theme> for egui::ThemePreference {
+    fn from(value: Theme) -> Self {
+        match value {
+            Theme::System => egui::ThemePreference::System,
+            Theme::Light => egui::ThemePreference::Light,
+            Theme::Dark => egui::ThemePreference::Dark,
+        }
+    }
+}
+
+/// View shown when the app starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StartupView {
+    LastUsed,
+    TimeTable,
+    Courses,
+}
+
+impl StartupView {
+    /// Matches `View::name`, None means whatever was open last
+    pub fn view_name(&self) -> Option<&'static str> {
+        match self {
+            StartupView::LastUsed => None,
+            StartupView::TimeTable => Some("Time Table"),
+            StartupView::Courses => Some("Courses"),
+        }
+    }
+}
+
+/// Portal times before 8:00 are afternoon times, shared with `ParseOptions`
+pub const DEFAULT_AFTERNOON_THRESHOLD_HOUR: u32 = 8;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub theme: Theme,
+
+    /// Times before this hour are afternoon times written in 12h, ie 1:00 -> 13:00
+    pub afternoon_threshold_hour: u32,
+
+    pub week_start: Weekday,
+    pub time_format: TimeFormat,
+    pub startup_view: StartupView,
+
+    /// Travel times used to flag rushed walks between sessions
+    pub campus_map: CampusMap,
+
+    /// Personal busy times, drawn on the time table
+    pub blocked_times: Vec<BlockedTime>,
+}
+
+impl Settings {
+    /// Pushes the process wide bits, the rest is read on demand
+    pub fn apply(&self, ctx: &egui::Context) {
+        ctx.set_theme(self.theme);
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: Theme::System,
+            afternoon_threshold_hour: DEFAULT_AFTERNOON_THRESHOLD_HOUR,
+            week_start: Weekday::Sat,
+            time_format: TimeFormat::TwentyFourHour,
+            startup_view: StartupView::TimeTable,
+            campus_map: CampusMap::default(),
+            blocked_times: Vec::new(),
+        }
+    }
+}
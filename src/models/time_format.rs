@@ -0,0 +1,38 @@
+use chrono::{NaiveDateTime, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeFormat {
+    TwentyFourHour,
+    TwelveHour,
+}
+
+impl TimeFormat {
+    pub fn format(&self, time: NaiveTime) -> String {
+        match self {
+            TimeFormat::TwentyFourHour => time.format("%H:%M").to_string(),
+            TimeFormat::TwelveHour => time.format("%-I:%M %p").to_string(),
+        }
+    }
+
+    /// Short label for a whole hour, used by axis headers
+    pub fn format_hour(&self, hour: u32) -> String {
+        match self {
+            TimeFormat::TwentyFourHour => format!("{}:00", hour),
+            TimeFormat::TwelveHour => {
+                let time = NaiveTime::from_hms_opt(hour % 24, 0, 0).unwrap_or_default();
+                let (is_pm, hour12) = time.hour12();
+                format!("{} {}", hour12, if is_pm { "PM" } else { "AM" })
+            }
+        }
+    }
+}
+
+/// Day and time, ie "21 Jan 2026 17:30"
+pub fn format_date_time(date_time: NaiveDateTime, time_format: TimeFormat) -> String {
+    format!(
+        "{} {}",
+        date_time.format("%-d %b %Y"),
+        time_format.format(date_time.time())
+    )
+}
@@ -0,0 +1,101 @@
+use chrono::{NaiveTime, Timelike, Weekday};
+
+/// Resolution of the occupancy grid
+pub const OCCUPANCY_SLOT_MINUTES: u32 = 5;
+
+const SLOTS_PER_DAY: u32 = 24 * 60 / OCCUPANCY_SLOT_MINUTES;
+const WORDS_PER_DAY: usize = SLOTS_PER_DAY.div_ceil(u64::BITS) as usize;
+
+/// Which 5 minute slots of the week are taken, one bit each
+/// Overlap checks are a bitwise AND instead of comparing every pair of sessions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeekOccupancy {
+    /// Bit n is set if anything is on day n (Monday first), skips empty days quickly
+    days: u8,
+    words: [[u64; WORDS_PER_DAY]; 7],
+
+    /// Some range didnt line up with the slots so it was rounded outwards
+    /// Overlaps involving it might be false positives, check the actual times
+    approximate: bool,
+}
+
+impl WeekOccupancy {
+    pub fn new() -> Self {
+        Self {
+            days: 0,
+            words: [[0; WORDS_PER_DAY]; 7],
+            approximate: false,
+        }
+    }
+
+    /// Marks [start_time, end_time), ranges off the grid are widened to whole slots
+    pub fn insert(&mut self, day: Weekday, start_time: NaiveTime, end_time: NaiveTime) {
+        let start_minute = start_time.num_seconds_from_midnight() / 60;
+        let end_minute = end_time.num_seconds_from_midnight().div_ceil(60);
+        if end_minute <= start_minute {
+            return;
+        }
+
+        let first_slot = start_minute / OCCUPANCY_SLOT_MINUTES;
+        let end_slot = end_minute
+            .div_ceil(OCCUPANCY_SLOT_MINUTES)
+            .min(SLOTS_PER_DAY);
+
+        self.approximate |= start_time.second() != 0
+            || end_time.second() != 0
+            || !start_minute.is_multiple_of(OCCUPANCY_SLOT_MINUTES)
+            || !end_minute.is_multiple_of(OCCUPANCY_SLOT_MINUTES);
+
+        let day_idx = day.num_days_from_monday() as usize;
+        self.days |= 1 << day_idx;
+
+        for slot in first_slot..end_slot {
+            self.words[day_idx][(slot / u64::BITS) as usize] |= 1 << (slot % u64::BITS);
+        }
+    }
+
+    pub fn union_with(&mut self, other: &WeekOccupancy) {
+        self.days |= other.days;
+        self.approximate |= other.approximate;
+
+        for (words, other_words) in self.words.iter_mut().zip(&other.words) {
+            for (word, other_word) in words.iter_mut().zip(other_words) {
+                *word |= other_word;
+            }
+        }
+    }
+
+    /// Share at least one slot, see `is_approximate`
+    pub fn overlaps(&self, other: &WeekOccupancy) -> bool {
+        let mut common_days = self.days & other.days;
+        while common_days != 0 {
+            let day_idx = common_days.trailing_zeros() as usize;
+            common_days &= common_days - 1;
+
+            if self.words[day_idx]
+                .iter()
+                .zip(&other.words[day_idx])
+                .any(|(word, other_word)| word & other_word != 0)
+            {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// True if `overlaps` can report ranges that only touch after rounding
+    pub fn is_approximate(&self) -> bool {
+        self.approximate
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.days == 0
+    }
+}
+
+impl Default for WeekOccupancy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -4,25 +4,9 @@ use cryn_rs::CrynApp;
 const DIMS: (f32, f32) = (1300.0, 800.0);
 
 pub fn run() {
-    // Scale dims to window
-    let window_size = display_info::DisplayInfo::all()
-        .ok()
-        .and_then(|displays| {
-            displays
-                .into_iter()
-                .find(|display| display.is_primary)
-                .map(|display| {
-                    (
-                        DIMS.0 * (display.width as f32) / 1920.0,
-                        DIMS.1 * (display.height as f32) / 1080.0,
-                    )
-                })
-        })
-        .unwrap_or(DIMS);
-
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
-            .with_inner_size(window_size)
+            .with_inner_size(get_window_size())
             .with_decorations(false),
 
         renderer: eframe::Renderer::Glow, // wow
@@ -36,3 +20,28 @@ pub fn run() {
     )
     .expect("Failed to start desktop app");
 }
+
+/// display-info is only pulled in on windows msvc
+#[cfg(all(target_os = "windows", target_env = "msvc", target_arch = "x86_64"))]
+fn get_window_size() -> (f32, f32) {
+    // Scale dims to window
+    display_info::DisplayInfo::all()
+        .ok()
+        .and_then(|displays| {
+            displays
+                .into_iter()
+                .find(|display| display.is_primary)
+                .map(|display| {
+                    (
+                        DIMS.0 * (display.width as f32) / 1920.0,
+                        DIMS.1 * (display.height as f32) / 1080.0,
+                    )
+                })
+        })
+        .unwrap_or(DIMS)
+}
+
+#[cfg(not(all(target_os = "windows", target_env = "msvc", target_arch = "x86_64")))]
+fn get_window_size() -> (f32, f32) {
+    DIMS
+}
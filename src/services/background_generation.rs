@@ -0,0 +1,266 @@
+use super::{
+    Schedule, ScheduleDiagnoser, ScheduleDiagnosis, ScheduleProblem, ScheduleSearch, TopSchedules,
+    score_schedule,
+};
+use crate::models::{CampusMap, SchedulePreferences};
+use std::sync::Arc;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc,
+};
+
+/// Bundles tried between two updates, a couple milliseconds worth
+const STEPS_PER_CHUNK: usize = 50_000;
+
+/// What the worker got to since the last update
+#[cfg(not(target_arch = "wasm32"))]
+struct GenerationUpdate {
+    /// Only when the best ones changed
+    ranked: Option<Vec<Schedule>>,
+    found: usize,
+    progress: f32,
+    done: bool,
+
+    /// Comes with the last update when nothing was found
+    diagnosis: Option<ScheduleDiagnosis>,
+}
+
+/// Every schedule gets scored, only the best `max_schedules` are kept
+/// Finding none at all gets diagnosed right after
+struct RankedSearch {
+    search: ScheduleSearch,
+    top: TopSchedules,
+    preferences: SchedulePreferences,
+    campus_map: CampusMap,
+    diagnoser: Option<ScheduleDiagnoser>,
+    diagnosis: Option<ScheduleDiagnosis>,
+}
+
+impl RankedSearch {
+    fn new(
+        problem: &ScheduleProblem,
+        preferences: SchedulePreferences,
+        campus_map: CampusMap,
+        max_schedules: usize,
+    ) -> Self {
+        Self {
+            search: ScheduleSearch::new(problem, usize::MAX),
+            top: TopSchedules::new(max_schedules),
+            preferences,
+            campus_map,
+            diagnoser: None,
+            diagnosis: None,
+        }
+    }
+
+    /// Returns the new best schedules if any made the cut
+    fn advance(&mut self, problem: &ScheduleProblem) -> Option<Vec<Schedule>> {
+        if let Some(diagnoser) = &mut self.diagnoser {
+            self.diagnosis = diagnoser.advance(problem, STEPS_PER_CHUNK);
+            if self.diagnosis.is_some() {
+                self.diagnoser = None;
+            }
+            return None;
+        }
+
+        let mut changed = false;
+        for schedule in self.search.advance(problem, STEPS_PER_CHUNK) {
+            let penalty =
+                score_schedule(problem, &schedule, &self.preferences, &self.campus_map).penalty;
+            changed |= self.top.push(penalty, schedule);
+        }
+
+        if self.search.is_done() && self.top.seen() == 0 && !problem.is_empty() {
+            self.diagnoser = Some(ScheduleDiagnoser::new(problem, self.top.capacity()));
+        }
+
+        changed.then(|| self.top.ranked())
+    }
+
+    fn is_done(&self) -> bool {
+        self.search.is_done() && self.diagnoser.is_none()
+    }
+}
+
+/// Schedule generation running off the render loop, poll it every frame
+/// Searches the whole space and keeps the best by the preferences, not just the first found
+/// Desktop searches on a worker thread, web has no threads so it searches a chunk per poll
+/// Dropping the job cancels it
+pub struct GenerationJob {
+    found: usize,
+    progress: f32,
+    done: bool,
+
+    /// Stopped before searching everything
+    cancelled: bool,
+
+    /// Why nothing was found, only after searching everything
+    diagnosis: Option<ScheduleDiagnosis>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    receiver: mpsc::Receiver<GenerationUpdate>,
+    #[cfg(not(target_arch = "wasm32"))]
+    cancel_flag: Arc<AtomicBool>,
+
+    #[cfg(target_arch = "wasm32")]
+    problem: Arc<ScheduleProblem>,
+    #[cfg(target_arch = "wasm32")]
+    search: RankedSearch,
+}
+
+impl GenerationJob {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn start(
+        problem: Arc<ScheduleProblem>,
+        preferences: SchedulePreferences,
+        campus_map: CampusMap,
+        max_schedules: usize,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+
+        let worker_cancel_flag = Arc::clone(&cancel_flag);
+        std::thread::spawn(move || {
+            let mut search = RankedSearch::new(&problem, preferences, campus_map, max_schedules);
+            loop {
+                if worker_cancel_flag.load(Ordering::Relaxed) {
+                    return;
+                }
+
+                let update = GenerationUpdate {
+                    ranked: search.advance(&problem),
+                    found: search.top.seen(),
+                    progress: search.search.progress(&problem),
+                    done: search.is_done(),
+                    diagnosis: search.diagnosis.take(),
+                };
+
+                // Receiver gone means the job was dropped
+                if sender.send(update).is_err() || search.is_done() {
+                    return;
+                }
+            }
+        });
+
+        Self {
+            found: 0,
+            progress: 0.0,
+            done: false,
+            cancelled: false,
+            diagnosis: None,
+            receiver,
+            cancel_flag,
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn start(
+        problem: Arc<ScheduleProblem>,
+        preferences: SchedulePreferences,
+        campus_map: CampusMap,
+        max_schedules: usize,
+    ) -> Self {
+        let search = RankedSearch::new(&problem, preferences, campus_map, max_schedules);
+
+        Self {
+            found: 0,
+            progress: 0.0,
+            done: search.is_done(),
+            cancelled: false,
+            diagnosis: None,
+            problem,
+            search,
+        }
+    }
+
+    /// Best schedules so far, best first, only when they changed since the last poll
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn poll(&mut self) -> Option<Vec<Schedule>> {
+        let mut ranked = None;
+        if self.done {
+            return ranked;
+        }
+
+        loop {
+            match self.receiver.try_recv() {
+                Ok(update) => {
+                    // Newer ones replace older ones
+                    if update.ranked.is_some() {
+                        ranked = update.ranked;
+                    }
+                    self.found = update.found;
+                    self.progress = update.progress;
+                    self.done = update.done;
+                    self.diagnosis = self.diagnosis.take().or(update.diagnosis);
+                }
+                Err(mpsc::TryRecvError::Empty) => break,
+
+                // Worker died, keep what it found
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.cancelled |= !self.done;
+                    self.done = true;
+                    break;
+                }
+            }
+        }
+
+        ranked
+    }
+
+    /// Best schedules so far, best first, only when they changed since the last poll
+    #[cfg(target_arch = "wasm32")]
+    pub fn poll(&mut self) -> Option<Vec<Schedule>> {
+        if self.done {
+            return None;
+        }
+
+        let ranked = self.search.advance(&self.problem);
+        self.found = self.search.top.seen();
+        self.progress = self.search.search.progress(&self.problem);
+        self.done = self.search.is_done();
+        self.diagnosis = self.search.diagnosis.take();
+        ranked
+    }
+
+    /// Stops searching, whatever was already found stays valid
+    pub fn cancel(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        self.cancel_flag.store(true, Ordering::Relaxed);
+
+        self.cancelled |= !self.done;
+        self.done = true;
+    }
+
+    /// Stopped early, finding nothing then proves nothing
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    /// Finished or got cancelled
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Why nothing was found, once done after searching everything
+    pub fn take_diagnosis(&mut self) -> Option<ScheduleDiagnosis> {
+        self.diagnosis.take()
+    }
+
+    /// Schedules found so far, kept or not
+    pub fn found(&self) -> usize {
+        self.found
+    }
+
+    /// Rough share of the search covered, 0 to 1
+    pub fn progress(&self) -> f32 {
+        self.progress
+    }
+}
+
+impl Drop for GenerationJob {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
@@ -0,0 +1,125 @@
+use crate::models::{CourseRecord, Semester};
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveTime, Utc, Weekday};
+use std::{cell::RefCell, rc::Rc};
+
+/// Lines longer than this many octets get folded, RFC 5545 3.1
+const MAX_LINE_OCTETS: usize = 75;
+
+const PRODUCT_ID: &str = "-//Cryn//Cryn Schedule//EN";
+
+/// Builds a calendar with one weekly event per record, repeating until the semester ends
+/// Times are floating so they land on the wall clock of whoever imports the file
+/// `timestamp` goes into DTSTAMP, pass it in so the output stays reproducible
+pub fn export_icalendar(
+    records: &[Rc<RefCell<CourseRecord>>],
+    semester: &Semester,
+    timestamp: DateTime<Utc>,
+) -> String {
+    let mut records: Vec<_> = records.iter().map(|record| record.borrow()).collect();
+    records.sort_by_cached_key(|record| {
+        (
+            record.course_definition.borrow().code.clone(),
+            record.record_type,
+            record.group,
+            record.day.num_days_from_monday(),
+            record.start_time,
+        )
+    });
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        format!("PRODID:{}", PRODUCT_ID),
+        "CALSCALE:GREGORIAN".to_owned(),
+        "METHOD:PUBLISH".to_owned(),
+    ];
+
+    for record in records {
+        let first_date =
+            semester.start + Days::new(record.day.days_since(semester.start.weekday()) as u64);
+        if first_date > semester.end {
+            continue;
+        }
+
+        let definition = record.course_definition.borrow();
+        lines.extend([
+            "BEGIN:VEVENT".to_owned(),
+            format!(
+                "UID:{}-{:?}-{}-{}-{}@cryn",
+                definition.code,
+                record.record_type,
+                record.group,
+                weekday_code(record.day),
+                record.start_time.format("%H%M")
+            ),
+            format!("DTSTAMP:{}", timestamp.format("%Y%m%dT%H%M%SZ")),
+            format!("DTSTART:{}", format_local(first_date, record.start_time)),
+            format!("DTEND:{}", format_local(first_date, record.end_time)),
+            format!(
+                "RRULE:FREQ=WEEKLY;BYDAY={};UNTIL={}",
+                weekday_code(record.day),
+                format_local(semester.end, NaiveTime::from_hms_opt(23, 59, 59).unwrap())
+            ),
+            format!(
+                "SUMMARY:{}",
+                escape_text(&format!(
+                    "{} {} - {:?} G{}",
+                    definition.code, definition.name, record.record_type, record.group
+                ))
+            ),
+            format!("LOCATION:{}", escape_text(&record.location.to_string())),
+            "END:VEVENT".to_owned(),
+        ]);
+    }
+
+    lines.push("END:VCALENDAR".to_owned());
+
+    lines
+        .iter()
+        .map(|line| fold_line(line))
+        .collect::<Vec<_>>()
+        .join("")
+}
+
+fn format_local(date: NaiveDate, time: NaiveTime) -> String {
+    date.and_time(time).format("%Y%m%dT%H%M%S").to_string()
+}
+
+fn weekday_code(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+/// TEXT values, RFC 5545 3.3.11
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Splits long lines without cutting through a utf8 char, ends every line with CRLF
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 2);
+    let mut line_octets = 0;
+
+    for ch in line.chars() {
+        if line_octets + ch.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            line_octets = 1;
+        }
+
+        folded.push(ch);
+        line_octets += ch.len_utf8();
+    }
+
+    folded.push_str("\r\n");
+    folded
+}
@@ -0,0 +1,168 @@
+use super::Session;
+use crate::models::{BlockedTime, CourseRecord, OrderedWeekday, TimeFormat};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+/// Two records that cant be attended together
+#[derive(Debug, Clone)]
+pub struct RecordConflict {
+    pub first: Rc<RefCell<CourseRecord>>,
+    pub second: Rc<RefCell<CourseRecord>>,
+}
+
+impl RecordConflict {
+    pub fn involves(&self, record: &Rc<RefCell<CourseRecord>>) -> bool {
+        Rc::ptr_eq(&self.first, record) || Rc::ptr_eq(&self.second, record)
+    }
+
+    /// The record on the other side of the conflict
+    pub fn other(&self, record: &Rc<RefCell<CourseRecord>>) -> Option<&Rc<RefCell<CourseRecord>>> {
+        if Rc::ptr_eq(&self.first, record) {
+            Some(&self.second)
+        } else if Rc::ptr_eq(&self.second, record) {
+            Some(&self.first)
+        } else {
+            None
+        }
+    }
+
+    /// Both records and when they overlap
+    pub fn describe(&self, time_format: TimeFormat) -> String {
+        let first = self.first.borrow();
+        let second = self.second.borrow();
+
+        format!(
+            "{} {:?} G{} clashes with {} {:?} G{} on {} ({} - {})",
+            first.course_definition.borrow().code,
+            first.record_type,
+            first.group,
+            second.course_definition.borrow().code,
+            second.record_type,
+            second.group,
+            OrderedWeekday::from(first.day),
+            time_format.format(first.start_time.max(second.start_time)),
+            time_format.format(first.end_time.min(second.end_time)),
+        )
+    }
+}
+
+/// Groups of the same course and type are alternatives, we only ever pick one of them
+pub fn records_are_alternatives(a: &CourseRecord, b: &CourseRecord) -> bool {
+    a.record_type == b.record_type
+        && a.group != b.group
+        && a.course_definition.borrow().code == b.course_definition.borrow().code
+}
+
+/// Are these two records actually competing for the same time?
+pub fn records_conflict(a: &CourseRecord, b: &CourseRecord) -> bool {
+    !records_are_alternatives(a, b) && Session::from_record(a).overlaps(&Session::from_record(b))
+}
+
+/// Every conflicting pair in the given records
+pub fn find_conflicts(records: &[Rc<RefCell<CourseRecord>>]) -> Vec<RecordConflict> {
+    // Sort by day then start so we only compare neighbours
+    let mut sorted: Vec<&Rc<RefCell<CourseRecord>>> = records.iter().collect();
+    sorted.sort_by_key(|record| {
+        let record = record.borrow();
+        (OrderedWeekday::from(record.day), record.start_time)
+    });
+
+    let mut conflicts = Vec::new();
+    for (i, first_rc) in sorted.iter().enumerate() {
+        let first = first_rc.borrow();
+
+        for second_rc in &sorted[(i + 1)..] {
+            let second = second_rc.borrow();
+            if second.day != first.day || second.start_time >= first.end_time {
+                break;
+            }
+
+            if records_conflict(&first, &second) {
+                conflicts.push(RecordConflict {
+                    first: Rc::clone(first_rc),
+                    second: Rc::clone(second_rc),
+                });
+            }
+        }
+    }
+
+    conflicts
+}
+
+/// A record during personal busy time
+#[derive(Debug, Clone)]
+pub struct BlockedConflict {
+    pub record: Rc<RefCell<CourseRecord>>,
+    pub blocked_time: BlockedTime,
+}
+
+impl BlockedConflict {
+    pub fn describe(&self, time_format: TimeFormat) -> String {
+        let record = self.record.borrow();
+
+        format!(
+            "{} {:?} G{} overlaps {}",
+            record.course_definition.borrow().code,
+            record.record_type,
+            record.group,
+            self.blocked_time.describe(time_format),
+        )
+    }
+}
+
+/// Every record overlapping one of the blocked times, hard or soft
+pub fn find_blocked_conflicts(
+    records: &[Rc<RefCell<CourseRecord>>],
+    blocked_times: &[BlockedTime],
+) -> Vec<BlockedConflict> {
+    records
+        .iter()
+        .flat_map(|record_rc| {
+            let session = Session::from_record(&record_rc.borrow());
+            blocked_times
+                .iter()
+                .filter(move |blocked_time| session.blocked_minutes(blocked_time) > 0)
+                .map(|blocked_time| BlockedConflict {
+                    record: Rc::clone(record_rc),
+                    blocked_time: blocked_time.clone(),
+                })
+        })
+        .collect()
+}
+
+/// Conflicts listed under every course code they involve
+pub fn group_conflicts_by_course(
+    conflicts: &[RecordConflict],
+) -> HashMap<String, Vec<RecordConflict>> {
+    let mut course_map = HashMap::<String, Vec<RecordConflict>>::new();
+
+    for conflict in conflicts {
+        let first_code = conflict
+            .first
+            .borrow()
+            .course_definition
+            .borrow()
+            .code
+            .clone();
+        let second_code = conflict
+            .second
+            .borrow()
+            .course_definition
+            .borrow()
+            .code
+            .clone();
+
+        if first_code != second_code {
+            course_map
+                .entry(second_code)
+                .or_default()
+                .push(conflict.clone());
+        }
+
+        course_map
+            .entry(first_code)
+            .or_default()
+            .push(conflict.clone());
+    }
+
+    course_map
+}
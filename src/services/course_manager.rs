@@ -1,10 +1,39 @@
-use super::parsers::standard_course_parser;
-use crate::models::{CourseDefinition, CourseRecord};
-use std::{cell::RefCell, rc::Rc};
+use super::parsers::{
+    CourseParser, ParseError, ParseInput, ParseOptions, ParseReport, ParserRegistry, PortalParser,
+    SpreadsheetMapping, SpreadsheetParser, Workbook, parse_spreadsheet,
+};
+use crate::models::{CourseDefinition, CourseRecord, RecordKey};
+use chrono::NaiveDateTime;
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+};
 
 pub struct CourseManager {
     pub course_definitions: Vec<Rc<RefCell<CourseDefinition>>>,
     pub course_records: Vec<Rc<RefCell<CourseRecord>>>, // Shouldve seen this coming lmao
+
+    /// Report of the currently loaded data
+    pub parse_report: ParseReport,
+
+    /// Where the currently loaded data came from, ie a file name
+    pub source_name: String,
+
+    /// Raw bytes the courses were parsed from, kept so settings changes can re-parse them
+    pub source_data: Vec<u8>,
+
+    /// Id of the parser that read it
+    pub parser_id: &'static str,
+
+    /// Columns picked for the loaded spreadsheet
+    pub spreadsheet_mapping: Option<SpreadsheetMapping>,
+}
+
+impl Default for CourseManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl CourseManager {
@@ -12,6 +41,11 @@ impl CourseManager {
         CourseManager {
             course_definitions: Vec::new(),
             course_records: Vec::new(),
+            parse_report: ParseReport::default(),
+            source_name: String::new(),
+            source_data: Vec::new(),
+            parser_id: PortalParser::ID,
+            spreadsheet_mapping: None,
         }
     }
 
@@ -37,14 +71,124 @@ impl CourseManager {
         }
     }
 
-    pub fn parse_courses(&mut self, data: &str) {
-        standard_course_parser::parse(self, data)
+    /// Parses into a detached manager with whichever parser fits best, nothing changes until it is committed
+    pub fn stage_courses(
+        registry: &ParserRegistry,
+        input: &ParseInput,
+        options: &ParseOptions,
+    ) -> Result<CourseManager, ParseError> {
+        let parser = registry.detect(input).ok_or(ParseError::UnknownFormat)?;
+        Self::stage_with(parser, input, options)
+    }
+
+    /// Same as `stage_courses` without the guessing
+    pub fn stage_courses_as(
+        registry: &ParserRegistry,
+        parser_id: &str,
+        input: &ParseInput,
+        options: &ParseOptions,
+    ) -> Result<CourseManager, ParseError> {
+        let parser = registry.get(parser_id).ok_or(ParseError::UnknownFormat)?;
+        Self::stage_with(parser, input, options)
+    }
+
+    fn stage_with(
+        parser: &dyn CourseParser,
+        input: &ParseInput,
+        options: &ParseOptions,
+    ) -> Result<CourseManager, ParseError> {
+        let mut staging = CourseManager::new();
+        staging.parse_report = parser.parse(&mut staging, input, options)?;
+        staging.source_data = input.bytes.to_vec();
+        staging.parser_id = parser.id();
+
+        Ok(staging)
+    }
+
+    /// Spreadsheet flavour of `stage_courses`, columns come from the mapping
+    pub fn stage_spreadsheet(
+        workbook: &Workbook,
+        mapping: &SpreadsheetMapping,
+        options: &ParseOptions,
+    ) -> Result<CourseManager, ParseError> {
+        let mut staging = CourseManager::new();
+        staging.parse_report = parse_spreadsheet(&mut staging, workbook, mapping, options)?;
+        staging.source_data = workbook.bytes.clone();
+        staging.parser_id = SpreadsheetParser::ID;
+        staging.spreadsheet_mapping = Some(mapping.clone());
+
+        Ok(staging)
+    }
+
+    /// Parses the loaded source again, ie after the parse options changed
+    pub fn restage(
+        &self,
+        registry: &ParserRegistry,
+        options: &ParseOptions,
+    ) -> Result<CourseManager, ParseError> {
+        let options = ParseOptions {
+            spreadsheet_mapping: self.spreadsheet_mapping.clone(),
+            ..options.clone()
+        };
+        let input = ParseInput::new(&self.source_name, &self.source_data);
+
+        Self::stage_courses_as(registry, self.parser_id, &input, &options)
+    }
+
+    /// Takes over the courses of a staged manager
+    pub fn commit_staged(&mut self, staged: CourseManager) {
+        self.course_definitions = staged.course_definitions;
+        self.course_records = staged.course_records;
+        self.parse_report = staged.parse_report;
+        self.source_data = staged.source_data;
+        self.parser_id = staged.parser_id;
+        self.spreadsheet_mapping = staged.spreadsheet_mapping;
+    }
+
+    /// Looks a record up by its `RecordKey`, ie to match it against another import
+    pub fn find_record(&self, key: &RecordKey) -> Option<Rc<RefCell<CourseRecord>>> {
+        self.course_records
+            .iter()
+            .find(|record| record.borrow().key() == *key)
+            .cloned()
+    }
+
+    /// When the portal took the loaded data, the latest record wins
+    pub fn snapshot_time(&self) -> Option<NaiveDateTime> {
+        self.course_records
+            .iter()
+            .filter_map(|record| record.borrow().snapshot_time)
+            .max()
+    }
+
+    /// Codes of the courses meeting in each building, buildings in numeric order
+    pub fn courses_by_building(&self) -> Vec<(String, BTreeSet<String>)> {
+        let mut buildings = BTreeMap::<(usize, String), BTreeSet<String>>::new();
+        for record in &self.course_records {
+            let record = record.borrow();
+            if let Some(building) = &record.location.building {
+                buildings
+                    .entry((building.len(), building.clone()))
+                    .or_default()
+                    .insert(record.course_definition.borrow().code.clone());
+            }
+        }
+
+        buildings
+            .into_iter()
+            .map(|((_, building), codes)| (building, codes))
+            .collect()
     }
 
+    /// Records of the selected courses, pinned types keep only their pinned group
     pub fn get_available_course_records(&self) -> Vec<Rc<RefCell<CourseRecord>>> {
         self.course_records
             .iter()
-            .filter(|&record| record.borrow().course_definition.borrow().selected)
+            .filter(|&record| {
+                let record = record.borrow();
+                let definition = record.course_definition.borrow();
+                definition.selected && definition.allows_group(record.record_type, record.group)
+            })
             .cloned() // &Rc<RefCell<CourseRecord>> -> Rc<RefCell<CourseRecord>>
             .collect()
     }
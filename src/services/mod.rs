@@ -1,4 +1,29 @@
+pub mod background_generation;
+pub mod calendar_exporter;
+pub mod conflict_detector;
 pub mod course_manager;
 mod parsers;
+pub mod schedule_diagnosis;
+pub mod schedule_generator;
+pub mod schedule_scorer;
+pub mod session_store;
+pub mod snapshot_diff;
+pub mod transfer_checker;
 
-pub use course_manager::*;
\ No newline at end of file
+pub use background_generation::*;
+pub use calendar_exporter::*;
+pub use conflict_detector::*;
+pub use course_manager::*;
+pub use parsers::{
+    CATALOGUE_FORMAT_VERSION, CatalogueCourse, CatalogueCsvParser, CatalogueFile,
+    CatalogueJsonParser, CatalogueRecord, CourseColumn, CourseParser, DuplicateParserId, HtmlTable,
+    ParseError, ParseInput, ParseOptions, ParseReport, ParserRegistry, PortalParser,
+    REQUIRED_SPREADSHEET_COLUMNS, RejectedRow, SpreadsheetMapping, SpreadsheetParser, Workbook,
+    catalogue_to_csv, catalogue_to_json, normalize_portal_text, read_html_tables,
+};
+pub use schedule_diagnosis::*;
+pub use schedule_generator::*;
+pub use schedule_scorer::*;
+pub use session_store::*;
+pub use snapshot_diff::*;
+pub use transfer_checker::*;
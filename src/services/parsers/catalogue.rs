@@ -0,0 +1,516 @@
+use super::{
+    CourseColumn, CourseParser, ParseError, ParseInput, ParseOptions, ParseReport, RejectedRow,
+    post_process_courses,
+};
+use crate::{
+    models::{CourseParseFormat, CourseRecord, CourseRecordType, Location, RecordStatus},
+    services::CourseManager,
+};
+use chrono::{NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+use std::{cell::RefCell, rc::Rc};
+
+/// Bumped on breaking changes only, new optional fields dont count
+pub const CATALOGUE_FORMAT_VERSION: u32 = 1;
+
+/// Times are always 24h in the catalogue
+const TIME_FORMAT: &str = "%H:%M";
+
+/// Portal snapshot time, iso without a zone
+const SNAPSHOT_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// Json catalogue, courses with their records nested
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "courses": [
+///     {
+///       "code": "CSE111",
+///       "name": "Programming",
+///       "records": [
+///         {
+///           "type": "Lecture", "group": 1, "day": "Sat", "from": "09:00", "to": "11:00",
+///           "class_size": 60, "enrolled": 42, "waiting": 0, "status": "Open", "location": "Hall 5",
+///           "id": 17, "date": "2026-01-21T17:30:00", "parse_format": "Standard"
+///         }
+///       ]
+///     }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CatalogueFile {
+    pub version: u32,
+    pub courses: Vec<CatalogueCourse>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CatalogueCourse {
+    pub code: String,
+    pub name: String,
+    pub records: Vec<CatalogueRecord>,
+}
+
+/// Kept as text so a bad value rejects its row instead of the whole file
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CatalogueRecord {
+    /// Lecture or Tutorial
+    #[serde(rename = "type")]
+    pub record_type: String,
+    pub group: i32,
+
+    /// Sat, Sun, ... full names work too
+    pub day: String,
+
+    /// HH:MM, 24h
+    pub from: String,
+    pub to: String,
+
+    pub class_size: i32,
+    pub enrolled: i32,
+    pub waiting: i32,
+
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub location: String,
+
+    /// Portal row id and snapshot time, only there if the source had them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+
+    /// `CourseParseFormat` of the source row, older files dont have it so it defaults to Standard
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parse_format: Option<String>,
+}
+
+/// Csv catalogue, one record per line with a header row, columns can come in any order
+/// code,name,type,group,day,from,to,class_size,enrolled,waiting,status,location
+const CSV_HEADERS: [(&str, CourseColumn); 12] = [
+    ("code", CourseColumn::Code),
+    ("name", CourseColumn::Name),
+    ("type", CourseColumn::Type),
+    ("group", CourseColumn::Group),
+    ("day", CourseColumn::Day),
+    ("from", CourseColumn::From),
+    ("to", CourseColumn::To),
+    ("class_size", CourseColumn::ClassSize),
+    ("enrolled", CourseColumn::Enrolled),
+    ("waiting", CourseColumn::Waiting),
+    ("status", CourseColumn::Status),
+    ("location", CourseColumn::Location),
+];
+
+/// Written after `CSV_HEADERS`, older files dont have them
+const OPTIONAL_CSV_HEADERS: [(&str, CourseColumn); 2] =
+    [("id", CourseColumn::Id), ("date", CourseColumn::Date)];
+
+/// Last of all, not a course column so it doesnt go through `CourseColumn`
+const PARSE_FORMAT_CSV_HEADER: &str = "parse_format";
+
+/// Reads `CatalogueFile` json
+pub struct CatalogueJsonParser;
+
+impl CatalogueJsonParser {
+    pub const ID: &str = "catalogue-json";
+}
+
+impl CourseParser for CatalogueJsonParser {
+    fn id(&self) -> &'static str {
+        Self::ID
+    }
+
+    fn detect(&self, input: &ParseInput) -> f32 {
+        let is_catalogue = serde_json::from_slice::<serde_json::Value>(input.bytes)
+            .is_ok_and(|value| value.get("courses").is_some_and(|c| c.is_array()));
+
+        match (is_catalogue, input.extension().as_deref()) {
+            (true, _) => 1.0,
+
+            // Broken catalogue, still ours so the error makes sense
+            (false, Some("json")) => 0.2,
+            _ => 0.0,
+        }
+    }
+
+    fn parse(
+        &self,
+        staging: &mut CourseManager,
+        input: &ParseInput,
+        _options: &ParseOptions,
+    ) -> Result<ParseReport, ParseError> {
+        parse_json(staging, &input.text())
+    }
+}
+
+/// Reads catalogue csv, see `CSV_HEADERS`
+pub struct CatalogueCsvParser;
+
+impl CatalogueCsvParser {
+    pub const ID: &str = "catalogue-csv";
+}
+
+impl CourseParser for CatalogueCsvParser {
+    fn id(&self) -> &'static str {
+        Self::ID
+    }
+
+    fn detect(&self, input: &ParseInput) -> f32 {
+        let text = input.text();
+        let header: Vec<String> = text
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or_default()
+            .split(',')
+            .map(|column| column.trim().to_lowercase())
+            .collect();
+        let has_headers = ["code", "day", "from", "to"]
+            .iter()
+            .all(|column| header.iter().any(|h| h == column));
+
+        match (has_headers, input.extension().as_deref()) {
+            (true, _) => 0.95,
+            (false, Some("csv")) => 0.2,
+            _ => 0.0,
+        }
+    }
+
+    fn parse(
+        &self,
+        staging: &mut CourseManager,
+        input: &ParseInput,
+        _options: &ParseOptions,
+    ) -> Result<ParseReport, ParseError> {
+        parse_csv(staging, &input.text())
+    }
+}
+
+pub fn catalogue_to_json(course_manager: &CourseManager) -> serde_json::Result<String> {
+    let courses = course_manager
+        .course_definitions
+        .iter()
+        .map(|def| {
+            let def = def.borrow();
+            CatalogueCourse {
+                code: def.code.clone(),
+                name: def.name.clone(),
+                records: course_manager
+                    .course_records
+                    .iter()
+                    .map(|record| record.borrow())
+                    .filter(|record| record.course_definition.borrow().code == def.code)
+                    .map(|record| CatalogueRecord {
+                        record_type: format!("{:?}", record.record_type),
+                        group: record.group,
+                        day: record.day.to_string(),
+                        from: record.start_time.format(TIME_FORMAT).to_string(),
+                        to: record.end_time.format(TIME_FORMAT).to_string(),
+                        class_size: record.class_size,
+                        enrolled: record.enrolled,
+                        waiting: record.waiting,
+                        status: record.status.to_string(),
+                        location: record.location.raw.clone(),
+                        id: record.id,
+                        date: record
+                            .snapshot_time
+                            .map(|time| time.format(SNAPSHOT_TIME_FORMAT).to_string()),
+                        parse_format: Some(format!("{:?}", record.parse_format)),
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&CatalogueFile {
+        version: CATALOGUE_FORMAT_VERSION,
+        courses,
+    })
+}
+
+pub fn catalogue_to_csv(course_manager: &CourseManager) -> Result<String, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(
+        CSV_HEADERS
+            .iter()
+            .chain(OPTIONAL_CSV_HEADERS.iter())
+            .map(|(header, _)| *header)
+            .chain([PARSE_FORMAT_CSV_HEADER]),
+    )?;
+
+    for record in &course_manager.course_records {
+        let record = record.borrow();
+        let def = record.course_definition.borrow();
+        writer.write_record([
+            def.code.clone(),
+            def.name.clone(),
+            format!("{:?}", record.record_type),
+            record.group.to_string(),
+            record.day.to_string(),
+            record.start_time.format(TIME_FORMAT).to_string(),
+            record.end_time.format(TIME_FORMAT).to_string(),
+            record.class_size.to_string(),
+            record.enrolled.to_string(),
+            record.waiting.to_string(),
+            record.status.to_string(),
+            record.location.raw.clone(),
+            record.id.map(|id| id.to_string()).unwrap_or_default(),
+            record
+                .snapshot_time
+                .map(|time| time.format(SNAPSHOT_TIME_FORMAT).to_string())
+                .unwrap_or_default(),
+            format!("{:?}", record.parse_format),
+        ])?;
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|err| csv::Error::from(err.into_error()))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+pub fn parse_json(
+    course_manager: &mut CourseManager,
+    data: &str,
+) -> Result<ParseReport, ParseError> {
+    let file: CatalogueFile =
+        serde_json::from_str(data).map_err(|err| ParseError::InvalidFormat(err.to_string()))?;
+    if file.version > CATALOGUE_FORMAT_VERSION {
+        return Err(ParseError::InvalidFormat(format!(
+            "Catalogue version {} is newer than supported ({})",
+            file.version, CATALOGUE_FORMAT_VERSION
+        )));
+    }
+
+    let mut report = ParseReport::default();
+    let rows = file.courses.iter().flat_map(|course| {
+        course
+            .records
+            .iter()
+            .map(move |record| (course.code.as_str(), course.name.as_str(), record))
+    });
+    for (row_index, (code, name, record)) in rows.enumerate() {
+        match add_record(course_manager, code, name, record, row_index) {
+            Ok(()) => report.imported += 1,
+            Err(rejected_row) => report.rejected.push(rejected_row),
+        }
+    }
+
+    finish(course_manager, report)
+}
+
+pub fn parse_csv(
+    course_manager: &mut CourseManager,
+    data: &str,
+) -> Result<ParseReport, ParseError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(data.as_bytes());
+
+    // Map columns by header name
+    let headers = reader
+        .headers()
+        .map_err(|err| ParseError::InvalidFormat(err.to_string()))?
+        .clone();
+    let mut column_indices = Vec::with_capacity(CSV_HEADERS.len());
+    for (header, column) in CSV_HEADERS {
+        let Some(idx) = headers.iter().position(|h| h.eq_ignore_ascii_case(header)) else {
+            return Err(ParseError::InvalidFormat(format!(
+                "Missing \"{}\" column",
+                header
+            )));
+        };
+        column_indices.push((column, idx));
+    }
+    for (header, column) in OPTIONAL_CSV_HEADERS {
+        if let Some(idx) = headers.iter().position(|h| h.eq_ignore_ascii_case(header)) {
+            column_indices.push((column, idx));
+        }
+    }
+    let parse_format_idx = headers
+        .iter()
+        .position(|h| h.eq_ignore_ascii_case(PARSE_FORMAT_CSV_HEADER));
+
+    let mut report = ParseReport::default();
+    for (row_index, row) in reader.records().enumerate() {
+        let row = match row {
+            Ok(row) => row,
+            Err(err) => {
+                report.rejected.push(RejectedRow {
+                    row_index,
+                    column: CourseColumn::Code,
+                    raw: String::new(),
+                    reason: err.to_string(),
+                });
+                continue;
+            }
+        };
+
+        let field = |column: CourseColumn| {
+            column_indices
+                .iter()
+                .find(|(c, _)| *c == column)
+                .and_then(|(_, idx)| row.get(*idx))
+                .unwrap_or("")
+        };
+        let parse_number = |column: CourseColumn| {
+            field(column).parse::<i32>().map_err(|err| RejectedRow {
+                row_index,
+                column,
+                raw: field(column).to_owned(),
+                reason: err.to_string(),
+            })
+        };
+
+        let record = (|| {
+            Ok(CatalogueRecord {
+                record_type: field(CourseColumn::Type).to_owned(),
+                group: parse_number(CourseColumn::Group)?,
+                day: field(CourseColumn::Day).to_owned(),
+                from: field(CourseColumn::From).to_owned(),
+                to: field(CourseColumn::To).to_owned(),
+                class_size: parse_number(CourseColumn::ClassSize)?,
+                enrolled: parse_number(CourseColumn::Enrolled)?,
+                waiting: parse_number(CourseColumn::Waiting)?,
+                status: field(CourseColumn::Status).to_owned(),
+                location: field(CourseColumn::Location).to_owned(),
+                id: match field(CourseColumn::Id) {
+                    "" => None,
+                    raw => Some(raw.parse::<u32>().map_err(|err| RejectedRow {
+                        row_index,
+                        column: CourseColumn::Id,
+                        raw: raw.to_owned(),
+                        reason: err.to_string(),
+                    })?),
+                },
+                date: Some(field(CourseColumn::Date))
+                    .filter(|date| !date.is_empty())
+                    .map(str::to_owned),
+                parse_format: parse_format_idx
+                    .and_then(|idx| row.get(idx))
+                    .filter(|parse_format| !parse_format.is_empty())
+                    .map(str::to_owned),
+            })
+        })();
+
+        let added = record.and_then(|record| {
+            add_record(
+                course_manager,
+                field(CourseColumn::Code),
+                field(CourseColumn::Name),
+                &record,
+                row_index,
+            )
+        });
+        match added {
+            Ok(()) => report.imported += 1,
+            Err(rejected_row) => report.rejected.push(rejected_row),
+        }
+    }
+
+    finish(course_manager, report)
+}
+
+fn finish(
+    course_manager: &mut CourseManager,
+    report: ParseReport,
+) -> Result<ParseReport, ParseError> {
+    if report.imported == 0 {
+        return Err(ParseError::NoRecords(report));
+    }
+
+    post_process_courses(course_manager);
+    Ok(report)
+}
+
+fn add_record(
+    course_manager: &mut CourseManager,
+    code: &str,
+    name: &str,
+    record: &CatalogueRecord,
+    row_index: usize,
+) -> Result<(), RejectedRow> {
+    let reject = |column: CourseColumn, raw: &str, reason: String| RejectedRow {
+        row_index,
+        column,
+        raw: raw.to_owned(),
+        reason,
+    };
+
+    if code.is_empty() {
+        return Err(reject(
+            CourseColumn::Code,
+            code,
+            "Missing course code".to_owned(),
+        ));
+    }
+
+    let record_type = record
+        .record_type
+        .parse::<CourseRecordType>()
+        .ok()
+        .filter(|record_type| *record_type != CourseRecordType::None)
+        .ok_or_else(|| {
+            reject(
+                CourseColumn::Type,
+                &record.record_type,
+                "Invalid course type".to_owned(),
+            )
+        })?;
+    let day = record
+        .day
+        .parse::<Weekday>()
+        .map_err(|err| reject(CourseColumn::Day, &record.day, err.to_string()))?;
+    let from = NaiveTime::parse_from_str(&record.from, TIME_FORMAT)
+        .map_err(|err| reject(CourseColumn::From, &record.from, err.to_string()))?;
+    let to = NaiveTime::parse_from_str(&record.to, TIME_FORMAT)
+        .map_err(|err| reject(CourseColumn::To, &record.to, err.to_string()))?;
+    let snapshot_time = record
+        .date
+        .as_ref()
+        .map(|date| {
+            NaiveDateTime::parse_from_str(date, SNAPSHOT_TIME_FORMAT)
+                .map_err(|err| reject(CourseColumn::Date, date, err.to_string()))
+        })
+        .transpose()?;
+
+    // Only a hint about the source layout, not worth rejecting the row over
+    let parse_format = record
+        .parse_format
+        .as_deref()
+        .and_then(|parse_format| parse_format.parse().ok())
+        .unwrap_or(CourseParseFormat::Standard);
+
+    let course_definition_rc = course_manager.get_or_add_course_definition(code, name);
+    match record_type {
+        CourseRecordType::Lecture => course_definition_rc.borrow_mut().lecture_count += 1,
+        CourseRecordType::Tutorial => course_definition_rc.borrow_mut().tutorial_count += 1,
+        CourseRecordType::None => unreachable!(),
+    }
+
+    let mut course_record = CourseRecord::new(
+        course_definition_rc,
+        record.group,
+        record_type,
+        day,
+        from,
+        to,
+        record.class_size,
+        record.enrolled,
+        record.waiting,
+        RecordStatus::parse(&record.status),
+        Location::parse(&record.location),
+        parse_format,
+    );
+    course_record.id = record.id;
+    course_record.snapshot_time = snapshot_time;
+
+    course_manager
+        .course_records
+        .push(Rc::new(RefCell::new(course_record)));
+
+    Ok(())
+}
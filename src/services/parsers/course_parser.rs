@@ -0,0 +1,48 @@
+use super::{ParseError, ParseOptions, ParseReport};
+use crate::services::CourseManager;
+use std::borrow::Cow;
+
+/// Raw course data as handed to us, a file or a paste
+pub struct ParseInput<'a> {
+    /// File name or a label like "Pasted data", only used as a hint
+    pub name: &'a str,
+    pub bytes: &'a [u8],
+}
+
+impl<'a> ParseInput<'a> {
+    pub fn new(name: &'a str, bytes: &'a [u8]) -> Self {
+        Self { name, bytes }
+    }
+
+    pub fn from_text(name: &'a str, text: &'a str) -> Self {
+        Self::new(name, text.as_bytes())
+    }
+
+    pub fn text(&self) -> Cow<'a, str> {
+        String::from_utf8_lossy(self.bytes)
+    }
+
+    /// Lowercase, without the dot
+    pub fn extension(&self) -> Option<String> {
+        self.name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension.to_ascii_lowercase())
+    }
+}
+
+/// One input format, registered in a `ParserRegistry`
+pub trait CourseParser {
+    /// Unique within a registry, ie "portal", also what the loaded data remembers to re-parse with
+    fn id(&self) -> &'static str;
+
+    /// How sure we are this parser understands the input, 0 means not at all and 1 certain
+    fn detect(&self, input: &ParseInput) -> f32;
+
+    /// Parses into the staging manager, which starts out empty
+    fn parse(
+        &self,
+        staging: &mut CourseManager,
+        input: &ParseInput,
+        options: &ParseOptions,
+    ) -> Result<ParseReport, ParseError>;
+}
@@ -0,0 +1,204 @@
+use html_escape::decode_html_entities;
+
+/// A `<table>` read into plain text cells
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HtmlTable {
+    /// Cells of the first row made only of `<th>`, empty if the table has none
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Reads every table in the document, nested ones come before the table holding them
+/// Doesnt care about attributes, missing closing tags or rows pasted without their `<table>`
+pub fn read_html_tables(html: &str) -> Vec<HtmlTable> {
+    let mut tables = Vec::new();
+    let mut open: Vec<TableBuilder> = Vec::new();
+    let mut rest = html;
+
+    while let Some(tag_start) = rest.find('<') {
+        if let Some(builder) = open.last_mut() {
+            builder.push_text(&rest[..tag_start]);
+        }
+        rest = &rest[tag_start..];
+
+        // Comments can hold anything, including tags
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[(end + 3)..]);
+            continue;
+        }
+
+        let Some(tag_end) = find_tag_end(rest) else {
+            break;
+        };
+        let tag = Tag::read(&rest[1..tag_end]);
+        rest = &rest[(tag_end + 1)..];
+
+        match (tag.name.as_str(), tag.closing) {
+            ("table", false) => open.push(TableBuilder::default()),
+            ("table", true) => {
+                if let Some(builder) = open.pop() {
+                    tables.push(builder.finish());
+                }
+            }
+            ("tr", false) => implicit_table(&mut open).start_row(),
+            ("tr", true) => {
+                if let Some(builder) = open.last_mut() {
+                    builder.finish_row();
+                }
+            }
+            ("td" | "th", false) => implicit_table(&mut open).start_cell(tag.name == "th"),
+            ("td" | "th", true) => {
+                if let Some(builder) = open.last_mut() {
+                    builder.finish_cell();
+                }
+            }
+            ("br" | "p" | "div", _) => {
+                if let Some(builder) = open.last_mut() {
+                    builder.push_text(" ");
+                }
+            }
+            ("script" | "style", false) => {
+                let close = format!("</{}", tag.name);
+                rest = rest
+                    .to_ascii_lowercase()
+                    .find(&close)
+                    .map_or("", |end| &rest[end..]);
+            }
+            _ => {}
+        }
+    }
+
+    // Unclosed tables
+    while let Some(builder) = open.pop() {
+        tables.push(builder.finish());
+    }
+
+    tables.retain(|table| !table.headers.is_empty() || !table.rows.is_empty());
+    tables
+}
+
+/// Index of the `>` closing the tag `rest` starts with, skipping any inside quoted attribute values
+fn find_tag_end(rest: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut after_equals = false;
+
+    for (idx, ch) in rest.char_indices() {
+        match quote {
+            Some(open) if ch == open => quote = None,
+            Some(_) => {}
+            None => match ch {
+                '>' => return Some(idx),
+
+                // Only values get quoted, an apostrophe anywhere else is just text
+                '"' | '\'' if after_equals => quote = Some(ch),
+                _ => {}
+            },
+        }
+
+        if !ch.is_whitespace() {
+            after_equals = quote.is_none() && ch == '=';
+        }
+    }
+
+    // Unbalanced quote, better to ignore it than to lose the rest of the document
+    rest.find('>')
+}
+
+/// Rows copied out of a table still count as one
+fn implicit_table(open: &mut Vec<TableBuilder>) -> &mut TableBuilder {
+    if open.is_empty() {
+        open.push(TableBuilder::default());
+    }
+
+    open.last_mut().unwrap()
+}
+
+struct Tag {
+    /// Lowercase
+    name: String,
+    closing: bool,
+}
+
+impl Tag {
+    /// Reads what is between the angle brackets
+    fn read(data: &str) -> Self {
+        let data = data.trim_start();
+        let (closing, data) = match data.strip_prefix('/') {
+            Some(data) => (true, data.trim_start()),
+            None => (false, data),
+        };
+
+        let name = data
+            .chars()
+            .take_while(|ch| ch.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+
+        Self { name, closing }
+    }
+}
+
+#[derive(Default)]
+struct TableBuilder {
+    table: HtmlTable,
+    row: Option<Vec<String>>,
+    row_has_data: bool,
+    cell: Option<String>,
+}
+
+impl TableBuilder {
+    fn push_text(&mut self, text: &str) {
+        if let Some(cell) = &mut self.cell {
+            cell.push_str(text);
+        }
+    }
+
+    fn start_row(&mut self) {
+        self.finish_row();
+        self.row = Some(Vec::new());
+    }
+
+    fn start_cell(&mut self, is_header: bool) {
+        self.finish_cell();
+        if self.row.is_none() {
+            self.start_row();
+        }
+
+        self.row_has_data |= !is_header;
+        self.cell = Some(String::new());
+    }
+
+    fn finish_cell(&mut self) {
+        let (Some(cell), Some(row)) = (self.cell.take(), &mut self.row) else {
+            return;
+        };
+
+        // Entities first so &nbsp; is collapsed too
+        let text = decode_html_entities(&cell);
+        row.push(text.split_whitespace().collect::<Vec<_>>().join(" "));
+    }
+
+    fn finish_row(&mut self) {
+        self.finish_cell();
+        let Some(row) = self.row.take() else {
+            return;
+        };
+
+        let has_data = std::mem::take(&mut self.row_has_data);
+        if row.is_empty() {
+            return;
+        }
+
+        if has_data {
+            self.table.rows.push(row);
+        } else if self.table.headers.is_empty() {
+            self.table.headers = row;
+        }
+        // Repeated header rows of paged tables are dropped
+    }
+
+    fn finish(mut self) -> HtmlTable {
+        self.finish_row();
+        self.table
+    }
+}
@@ -1 +1,21 @@
+mod catalogue;
+mod course_parser;
+mod html_table;
+mod parse_options;
+mod parse_report;
+mod parser_registry;
+mod portal_text;
+mod post_process;
+mod spreadsheet;
 pub mod standard_course_parser;
+
+pub use catalogue::*;
+pub use course_parser::*;
+pub use html_table::*;
+pub use parse_options::*;
+pub use parse_report::*;
+pub use parser_registry::*;
+pub use portal_text::*;
+pub use post_process::*;
+pub use spreadsheet::*;
+pub use standard_course_parser::{PortalParser, fix_timespan, parse_snapshot_time};
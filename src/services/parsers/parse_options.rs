@@ -0,0 +1,21 @@
+use super::SpreadsheetMapping;
+use crate::models::DEFAULT_AFTERNOON_THRESHOLD_HOUR;
+
+/// Knobs the user can turn from the settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Times before this hour are afternoon times written in 12h, ie 1:00 -> 13:00
+    pub afternoon_threshold_hour: u32,
+
+    /// Columns picked in the mapping dialog, detected when missing
+    pub spreadsheet_mapping: Option<SpreadsheetMapping>,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            afternoon_threshold_hour: DEFAULT_AFTERNOON_THRESHOLD_HOUR,
+            spreadsheet_mapping: None,
+        }
+    }
+}
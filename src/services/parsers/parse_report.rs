@@ -0,0 +1,143 @@
+use std::fmt;
+
+/// Columns of a course row as exposed by the registration portal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CourseColumn {
+    Id,
+    Code,
+    Name,
+    Group,
+    Type,
+    Day,
+    From,
+    To,
+    ClassSize,
+    Enrolled,
+    Waiting,
+    Status,
+    Location,
+    Date,
+}
+
+impl CourseColumn {
+    /// In portal order
+    pub const ALL: [CourseColumn; 14] = [
+        CourseColumn::Id,
+        CourseColumn::Code,
+        CourseColumn::Name,
+        CourseColumn::Group,
+        CourseColumn::Type,
+        CourseColumn::Day,
+        CourseColumn::From,
+        CourseColumn::To,
+        CourseColumn::ClassSize,
+        CourseColumn::Enrolled,
+        CourseColumn::Waiting,
+        CourseColumn::Status,
+        CourseColumn::Location,
+        CourseColumn::Date,
+    ];
+}
+
+impl fmt::Display for CourseColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Same naming as the portal headers
+        let name = match self {
+            CourseColumn::Id => "id",
+            CourseColumn::Code => "Code",
+            CourseColumn::Name => "Name",
+            CourseColumn::Group => "Group",
+            CourseColumn::Type => "Type",
+            CourseColumn::Day => "Day",
+            CourseColumn::From => "From",
+            CourseColumn::To => "To",
+            CourseColumn::ClassSize => "Class Size",
+            CourseColumn::Enrolled => "Enrolled",
+            CourseColumn::Waiting => "Waiting",
+            CourseColumn::Status => "Status",
+            CourseColumn::Location => "Location",
+            CourseColumn::Date => "Date",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// A single row that was skipped during parsing
+#[derive(Debug, Clone)]
+pub struct RejectedRow {
+    pub row_index: usize,
+    pub column: CourseColumn,
+    pub raw: String,
+    pub reason: String,
+}
+
+impl fmt::Display for RejectedRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Row {}, {}: \"{}\" ({})",
+            self.row_index + 1,
+            self.column,
+            self.raw,
+            self.reason
+        )
+    }
+}
+
+/// Outcome of a parse, rows that made it in and rows that didnt
+#[derive(Debug, Clone, Default)]
+pub struct ParseReport {
+    pub imported: usize,
+    pub rejected: Vec<RejectedRow>,
+}
+
+impl ParseReport {
+    pub fn skipped(&self) -> usize {
+        self.rejected.len()
+    }
+
+    pub fn total(&self) -> usize {
+        self.imported + self.skipped()
+    }
+}
+
+impl fmt::Display for ParseReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Imported {} rows, skipped {}",
+            self.imported,
+            self.skipped()
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseError {
+    /// Nothing usable in the input, existing data is kept as is
+    NoRecords(ParseReport),
+
+    /// Input isnt in the expected shape at all, ie broken json
+    InvalidFormat(String),
+
+    /// No registered parser recognised the input
+    UnknownFormat,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::NoRecords(report) if report.total() == 0 => {
+                write!(f, "No course rows found")
+            }
+            ParseError::NoRecords(report) => {
+                write!(f, "All {} course rows were rejected", report.skipped())
+            }
+            ParseError::InvalidFormat(reason) => write!(f, "Invalid format: {}", reason),
+            ParseError::UnknownFormat => write!(f, "Unrecognised course data"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -0,0 +1,77 @@
+use super::{
+    CatalogueCsvParser, CatalogueJsonParser, CourseParser, ParseInput, PortalParser,
+    SpreadsheetParser,
+};
+use std::fmt;
+
+/// A parser with that id is already registered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateParserId(pub &'static str);
+
+impl fmt::Display for DuplicateParserId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "A parser with id \"{}\" is already registered", self.0)
+    }
+}
+
+impl std::error::Error for DuplicateParserId {}
+
+/// Parsers we try input against, the most confident one wins
+pub struct ParserRegistry {
+    parsers: Vec<Box<dyn CourseParser>>,
+}
+
+impl Default for ParserRegistry {
+    fn default() -> Self {
+        Self::with_builtin_parsers()
+    }
+}
+
+impl ParserRegistry {
+    pub fn new() -> Self {
+        Self {
+            parsers: Vec::new(),
+        }
+    }
+
+    pub fn with_builtin_parsers() -> Self {
+        Self {
+            parsers: vec![
+                Box::new(PortalParser),
+                Box::new(CatalogueJsonParser),
+                Box::new(CatalogueCsvParser),
+                Box::new(SpreadsheetParser),
+            ],
+        }
+    }
+
+    /// Ids are unique, a faculty specific parser gets its own next to the builtin ones
+    /// Later registrations win detection ties
+    pub fn register<P: CourseParser + 'static>(
+        &mut self,
+        parser: P,
+    ) -> Result<(), DuplicateParserId> {
+        if self.get(parser.id()).is_some() {
+            return Err(DuplicateParserId(parser.id()));
+        }
+
+        self.parsers.push(Box::new(parser));
+        Ok(())
+    }
+
+    pub fn detect(&self, input: &ParseInput) -> Option<&dyn CourseParser> {
+        self.parsers
+            .iter()
+            .map(|parser| (parser, parser.detect(input)))
+            .filter(|(_, confidence)| *confidence > 0.0)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(parser, _)| &**parser)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&dyn CourseParser> {
+        self.parsers
+            .iter()
+            .find(|parser| parser.id() == id)
+            .map(|parser| &**parser)
+    }
+}
@@ -0,0 +1,59 @@
+/// Network tab copies can be escaped more than once
+const MAX_UNESCAPE_PASSES: usize = 4;
+
+/// Turns whatever the student copied into plain table html
+/// Handles the raw Ext.net script response (json escaped, possibly twice) and plain html tables
+pub fn normalize_portal_text(data: &str) -> String {
+    let mut text = data.trim().to_owned();
+
+    for _ in 0..MAX_UNESCAPE_PASSES {
+        if !looks_escaped(&text) {
+            break;
+        }
+
+        text = unescape_json_str(&text);
+    }
+
+    text
+}
+
+fn looks_escaped(text: &str) -> bool {
+    text.contains("\\\"") || text.contains("\\u003c") || text.contains("\\u003C")
+}
+
+/// Json string escapes, unknown escapes are kept as is
+fn unescape_json_str(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            result.push(ch);
+            continue;
+        }
+
+        match chars.next() {
+            Some('"') => result.push('"'),
+            Some('\\') => result.push('\\'),
+            Some('/') => result.push('/'),
+            Some('n') | Some('r') | Some('t') => result.push(' '),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    Some(decoded) => result.push(decoded),
+                    None => {
+                        result.push_str("\\u");
+                        result.push_str(&hex);
+                    }
+                }
+            }
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+
+    result
+}
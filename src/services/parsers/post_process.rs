@@ -0,0 +1,79 @@
+use crate::{
+    models::{CourseFlags, CourseRecord, CourseRecordType, OrderedWeekday},
+    services::CourseManager,
+};
+use std::collections::HashMap;
+
+/// Sorts freshly parsed courses and works out their flags, every parser ends with this
+pub fn post_process_courses(course_manager: &mut CourseManager) {
+    // Sort course definitions by code
+    course_manager
+        .course_definitions
+        .sort_by(|a, b| a.borrow().code.cmp(&b.borrow().code));
+
+    // Sort records by day then time
+    course_manager.course_records.sort_by_key(|record| {
+        (
+            OrderedWeekday::from(record.borrow().day),
+            record.borrow().start_time,
+        )
+    });
+
+    // Flags
+    course_manager.course_definitions.iter().for_each(|def_rc| {
+        let mut lecture_group_map = HashMap::<i32, i32>::new();
+        let mut tutorial_group_map = HashMap::<i32, i32>::new();
+
+        // How many lecs/tuts for each group?
+        let mut count_groups = |record: &mut CourseRecord, record_type: CourseRecordType| {
+            if record.record_type != record_type {
+                return;
+            }
+
+            let group_map: &mut HashMap<i32, i32>;
+            let mul_index: &mut i32;
+
+            match record_type {
+                CourseRecordType::Lecture => {
+                    group_map = &mut lecture_group_map;
+                    mul_index = &mut record.mullec_index;
+                }
+
+                CourseRecordType::Tutorial => {
+                    group_map = &mut tutorial_group_map;
+                    mul_index = &mut record.multut_index;
+                }
+
+                CourseRecordType::None => return,
+            }
+
+            // Increment
+            let count = group_map.entry(record.group).or_insert(0);
+            *count += 1;
+            *mul_index = *count;
+        };
+
+        // Start counting
+        course_manager
+            .course_records
+            .iter_mut()
+            .filter(|record| {
+                record.borrow().course_definition.borrow().code == def_rc.borrow().code
+            })
+            .for_each(|record| {
+                let record = &mut *record.borrow_mut();
+                count_groups(record, CourseRecordType::Lecture);
+                count_groups(record, CourseRecordType::Tutorial);
+            });
+
+        // Check for MultipleLectures
+        if lecture_group_map.iter().any(|(_, count)| *count > 1) {
+            def_rc.borrow_mut().flags |= CourseFlags::MultipleLectures;
+        }
+
+        // Check for MultipleTutorials
+        if tutorial_group_map.iter().any(|(_, count)| *count > 1) {
+            def_rc.borrow_mut().flags |= CourseFlags::MultipleTutorials;
+        }
+    });
+}
@@ -0,0 +1,435 @@
+use super::{
+    CourseColumn, CourseParser, ParseError, ParseInput, ParseOptions, ParseReport, RejectedRow,
+    fix_timespan, parse_snapshot_time, post_process_courses,
+};
+use crate::{
+    models::{CourseParseFormat, CourseRecord, CourseRecordType, Location, RecordStatus},
+    services::CourseManager,
+};
+use calamine::{Data, Reader};
+use chrono::{Days, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use std::{cell::RefCell, collections::HashMap, io::Cursor, rc::Rc};
+
+/// Titles and notes usually sit above the header row
+const MAX_HEADER_SEARCH_ROWS: usize = 10;
+
+/// Columns a sheet has to provide, the rest default to empty/zero
+pub const REQUIRED_SPREADSHEET_COLUMNS: [CourseColumn; 6] = [
+    CourseColumn::Code,
+    CourseColumn::Group,
+    CourseColumn::Type,
+    CourseColumn::Day,
+    CourseColumn::From,
+    CourseColumn::To,
+];
+
+/// Titles we recognise for each column, compared after `normalize_header`
+const HEADER_ALIASES: [(CourseColumn, &[&str]); 14] = [
+    (CourseColumn::Id, &["id", "record id"]),
+    (
+        CourseColumn::Code,
+        &["code", "course", "course code", "course no", "subject code"],
+    ),
+    (
+        CourseColumn::Name,
+        &["name", "title", "course name", "course title", "subject"],
+    ),
+    (
+        CourseColumn::Group,
+        &["group", "grp", "group no", "section", "sec", "section no"],
+    ),
+    (
+        CourseColumn::Type,
+        &["type", "kind", "class type", "course type", "session type"],
+    ),
+    (CourseColumn::Day, &["day", "days", "weekday"]),
+    (
+        CourseColumn::From,
+        &["from", "start", "start time", "from time", "begins"],
+    ),
+    (
+        CourseColumn::To,
+        &["to", "end", "end time", "to time", "ends", "finish"],
+    ),
+    (
+        CourseColumn::ClassSize,
+        &["size", "class size", "capacity", "seats"],
+    ),
+    (
+        CourseColumn::Enrolled,
+        &["enrolled", "registered", "enrollment", "enrolment"],
+    ),
+    (
+        CourseColumn::Waiting,
+        &["waiting", "wait", "waitlist", "wait list", "waiting list"],
+    ),
+    (CourseColumn::Status, &["status", "state"]),
+    (
+        CourseColumn::Location,
+        &["location", "room", "hall", "venue"],
+    ),
+    (CourseColumn::Date, &["date", "snapshot", "snapshot time"]),
+];
+
+/// Lowercase words, ie "Course  Code:" becomes "course code"
+fn normalize_header(header: &str) -> String {
+    header
+        .to_lowercase()
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Which sheet to read and where each course column lives in it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpreadsheetMapping {
+    pub sheet: String,
+
+    /// Row holding the column titles, data starts right after it
+    pub header_row: usize,
+
+    /// Sheet column index of each course column, unmapped columns are left out
+    pub columns: HashMap<CourseColumn, usize>,
+}
+
+impl SpreadsheetMapping {
+    pub fn missing_columns(&self) -> Vec<CourseColumn> {
+        REQUIRED_SPREADSHEET_COLUMNS
+            .into_iter()
+            .filter(|column| !self.columns.contains_key(column))
+            .collect()
+    }
+
+    /// Maps columns whose titles are one of ours, ie "Course Code" or "Start"
+    /// Whole titles only, so "Room Name" or "Postcode" stay unmapped
+    pub fn guess(sheet: &str, header_row: usize, headers: &[String]) -> Self {
+        let mut columns = HashMap::new();
+
+        for (idx, header) in headers.iter().enumerate() {
+            let header = normalize_header(header);
+            let Some((column, _)) = HEADER_ALIASES
+                .iter()
+                .find(|(_, aliases)| aliases.contains(&header.as_str()))
+            else {
+                continue;
+            };
+
+            // First match wins
+            columns.entry(*column).or_insert(idx);
+        }
+
+        Self {
+            sheet: sheet.to_owned(),
+            header_row,
+            columns,
+        }
+    }
+
+    /// Looks for a header row with every required column near the top of each sheet
+    pub fn detect(workbook: &Workbook) -> Option<Self> {
+        workbook.sheets.iter().find_map(|(sheet, rows)| {
+            rows.iter()
+                .take(MAX_HEADER_SEARCH_ROWS)
+                .enumerate()
+                .map(|(header_row, headers)| Self::guess(sheet, header_row, headers))
+                .find(|mapping| mapping.missing_columns().is_empty())
+        })
+    }
+}
+
+/// Zip (xlsx, ods) and compound file (xls) signatures
+const SPREADSHEET_MAGIC: [&[u8]; 2] = [b"PK\x03\x04", b"\xD0\xCF\x11\xE0"];
+
+/// Reads xlsx, xls and ods, columns come from `ParseOptions::spreadsheet_mapping` or are detected
+pub struct SpreadsheetParser;
+
+impl SpreadsheetParser {
+    pub const ID: &str = "spreadsheet";
+}
+
+impl CourseParser for SpreadsheetParser {
+    fn id(&self) -> &'static str {
+        Self::ID
+    }
+
+    fn detect(&self, input: &ParseInput) -> f32 {
+        if SPREADSHEET_MAGIC
+            .iter()
+            .any(|magic| input.bytes.starts_with(magic))
+        {
+            0.9
+        } else {
+            0.0
+        }
+    }
+
+    fn parse(
+        &self,
+        staging: &mut CourseManager,
+        input: &ParseInput,
+        options: &ParseOptions,
+    ) -> Result<ParseReport, ParseError> {
+        let workbook = Workbook::read(input.bytes)?;
+        let mapping = options
+            .spreadsheet_mapping
+            .clone()
+            .or_else(|| SpreadsheetMapping::detect(&workbook))
+            .ok_or_else(|| {
+                ParseError::InvalidFormat("Couldn't find the course columns".to_owned())
+            })?;
+
+        let report = parse_spreadsheet(staging, &workbook, &mapping, options)?;
+        staging.spreadsheet_mapping = Some(mapping);
+
+        Ok(report)
+    }
+}
+
+/// A workbook read into plain text cells
+pub struct Workbook {
+    /// The file as loaded, kept for re-parsing
+    pub bytes: Vec<u8>,
+    pub sheets: Vec<(String, Vec<Vec<String>>)>,
+}
+
+impl Workbook {
+    /// Reads xlsx, xlsm, xls and ods
+    pub fn read(bytes: &[u8]) -> Result<Self, ParseError> {
+        let mut workbook = calamine::open_workbook_auto_from_rs(Cursor::new(bytes))
+            .map_err(|err| ParseError::InvalidFormat(err.to_string()))?;
+
+        let mut sheets = Vec::new();
+        for sheet_name in workbook.sheet_names() {
+            let range = workbook
+                .worksheet_range(&sheet_name)
+                .map_err(|err| ParseError::InvalidFormat(err.to_string()))?;
+            let rows = range
+                .rows()
+                .map(|row| row.iter().map(cell_text).collect())
+                .collect();
+
+            sheets.push((sheet_name, rows));
+        }
+
+        if sheets.is_empty() {
+            return Err(ParseError::InvalidFormat(
+                "Workbook has no sheets".to_owned(),
+            ));
+        }
+
+        Ok(Self {
+            bytes: bytes.to_vec(),
+            sheets,
+        })
+    }
+
+    pub fn rows(&self, sheet: &str) -> Option<&[Vec<String>]> {
+        self.sheets
+            .iter()
+            .find(|(name, _)| name == sheet)
+            .map(|(_, rows)| rows.as_slice())
+    }
+}
+
+/// Times come out as HH:MM, dates as iso, whole numbers without the trailing .0
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Empty | Data::Error(_) => String::new(),
+        Data::Float(value) if value.fract() == 0.0 => format!("{}", *value as i64),
+        Data::DateTime(value) if value.as_f64() >= 1.0 => date_time_from_serial(value.as_f64())
+            .map(|date_time| date_time.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default(),
+        Data::DateTime(value) => time_from_day_fraction(value.as_f64())
+            .map(|time| time.format("%H:%M").to_string())
+            .unwrap_or_default(),
+        other => other.to_string().trim().to_owned(),
+    }
+}
+
+/// Days since 1899-12-30, the fraction being the time of day
+fn date_time_from_serial(value: f64) -> Option<NaiveDateTime> {
+    let date = NaiveDate::from_ymd_opt(1899, 12, 30)?.checked_add_days(Days::new(value as u64))?;
+    Some(date.and_time(time_from_day_fraction(value)?))
+}
+
+/// Excel stores times as a fraction of a day
+fn time_from_day_fraction(value: f64) -> Option<NaiveTime> {
+    let seconds = (value.fract() * 86400.0).round() as u32;
+    NaiveTime::from_num_seconds_from_midnight_opt(seconds % 86400, 0)
+}
+
+pub fn parse_spreadsheet(
+    course_manager: &mut CourseManager,
+    workbook: &Workbook,
+    mapping: &SpreadsheetMapping,
+    options: &ParseOptions,
+) -> Result<ParseReport, ParseError> {
+    let Some(rows) = workbook.rows(&mapping.sheet) else {
+        return Err(ParseError::InvalidFormat(format!(
+            "Sheet \"{}\" not found",
+            mapping.sheet
+        )));
+    };
+
+    let missing_columns = mapping.missing_columns();
+    if !missing_columns.is_empty() {
+        let missing: Vec<String> = missing_columns.iter().map(|c| c.to_string()).collect();
+        return Err(ParseError::InvalidFormat(format!(
+            "Unmapped columns: {}",
+            missing.join(", ")
+        )));
+    }
+
+    let mut report = ParseReport::default();
+    for (row_index, row) in rows.iter().enumerate().skip(mapping.header_row + 1) {
+        // Blank separator rows are common in faculty sheets
+        if row.iter().all(|cell| cell.is_empty()) {
+            continue;
+        }
+
+        match parse_row(course_manager, row, row_index, mapping, options) {
+            Ok(()) => report.imported += 1,
+            Err(rejected_row) => report.rejected.push(rejected_row),
+        }
+    }
+
+    if report.imported == 0 {
+        return Err(ParseError::NoRecords(report));
+    }
+
+    post_process_courses(course_manager);
+    Ok(report)
+}
+
+fn parse_row(
+    course_manager: &mut CourseManager,
+    row: &[String],
+    row_index: usize,
+    mapping: &SpreadsheetMapping,
+    options: &ParseOptions,
+) -> Result<(), RejectedRow> {
+    let field = |column: CourseColumn| {
+        mapping
+            .columns
+            .get(&column)
+            .and_then(|idx| row.get(*idx))
+            .map_or("", |cell| cell.trim())
+    };
+    let reject = |column: CourseColumn, reason: String| RejectedRow {
+        row_index,
+        column,
+        raw: field(column).to_owned(),
+        reason,
+    };
+
+    let code = field(CourseColumn::Code);
+    if code.is_empty() {
+        return Err(reject(CourseColumn::Code, "Missing course code".to_owned()));
+    }
+
+    // Sheets dont always carry names
+    let name = match field(CourseColumn::Name) {
+        "" => code,
+        name => name,
+    };
+
+    let group = parse_group(field(CourseColumn::Group))
+        .ok_or_else(|| reject(CourseColumn::Group, "Invalid group".to_owned()))?;
+    let record_type = parse_record_type(field(CourseColumn::Type))
+        .ok_or_else(|| reject(CourseColumn::Type, "Invalid course type".to_owned()))?;
+    let day = field(CourseColumn::Day)
+        .parse::<Weekday>()
+        .map_err(|err| reject(CourseColumn::Day, err.to_string()))?;
+
+    let mut from = parse_time(field(CourseColumn::From))
+        .ok_or_else(|| reject(CourseColumn::From, "Invalid time".to_owned()))?;
+    let mut to = parse_time(field(CourseColumn::To))
+        .ok_or_else(|| reject(CourseColumn::To, "Invalid time".to_owned()))?;
+
+    // Same 12h shorthand as the portal unless am/pm is spelled out
+    if !has_meridiem(field(CourseColumn::From)) {
+        fix_timespan(&mut from, options.afternoon_threshold_hour);
+    }
+    if !has_meridiem(field(CourseColumn::To)) {
+        fix_timespan(&mut to, options.afternoon_threshold_hour);
+    }
+
+    let parse_count = |column: CourseColumn| match field(column) {
+        "" => Ok(0),
+        raw => raw
+            .parse::<i32>()
+            .map_err(|err| reject(column, err.to_string())),
+    };
+    let class_size = parse_count(CourseColumn::ClassSize)?;
+    let enrolled = parse_count(CourseColumn::Enrolled)?;
+    let waiting = parse_count(CourseColumn::Waiting)?;
+
+    let course_definition_rc = course_manager.get_or_add_course_definition(code, name);
+    match record_type {
+        CourseRecordType::Lecture => course_definition_rc.borrow_mut().lecture_count += 1,
+        CourseRecordType::Tutorial => course_definition_rc.borrow_mut().tutorial_count += 1,
+        CourseRecordType::None => unreachable!(),
+    }
+
+    let mut record = CourseRecord::new(
+        course_definition_rc,
+        group,
+        record_type,
+        day,
+        from,
+        to,
+        class_size,
+        enrolled,
+        waiting,
+        RecordStatus::parse(field(CourseColumn::Status)),
+        Location::parse(field(CourseColumn::Location)),
+        CourseParseFormat::Excel,
+    );
+    record.id = field(CourseColumn::Id).parse().ok();
+    record.snapshot_time = parse_snapshot_time(field(CourseColumn::Date));
+
+    course_manager
+        .course_records
+        .push(Rc::new(RefCell::new(record)));
+
+    Ok(())
+}
+
+/// Plain numbers or prefixed ones like "G3"
+fn parse_group(raw: &str) -> Option<i32> {
+    raw.trim_start_matches(|ch: char| !ch.is_ascii_digit())
+        .parse()
+        .ok()
+}
+
+/// Lec/Lecture, Tut/Tutorial, labs and sections count as tutorials
+fn parse_record_type(raw: &str) -> Option<CourseRecordType> {
+    let raw = raw.trim().to_lowercase();
+    if raw.starts_with("lec") {
+        Some(CourseRecordType::Lecture)
+    } else if raw.starts_with("tut") || raw.starts_with("lab") || raw.starts_with("sec") {
+        Some(CourseRecordType::Tutorial)
+    } else {
+        None
+    }
+}
+
+fn has_meridiem(raw: &str) -> bool {
+    let raw = raw.to_lowercase();
+    raw.ends_with("am") || raw.ends_with("pm")
+}
+
+fn parse_time(raw: &str) -> Option<NaiveTime> {
+    [
+        "%H:%M",
+        "%H:%M:%S",
+        "%I:%M %p",
+        "%I:%M%p",
+        "%I %p",
+        "%Y-%m-%d %H:%M:%S",
+    ]
+    .iter()
+    .find_map(|format| NaiveTime::parse_from_str(raw, format).ok())
+    .or_else(|| raw.parse::<f64>().ok().and_then(time_from_day_fraction))
+}
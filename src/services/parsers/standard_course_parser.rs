@@ -1,185 +1,274 @@
+use super::{
+    CourseColumn, CourseParser, HtmlTable, ParseError, ParseInput, ParseOptions, ParseReport,
+    RejectedRow, normalize_portal_text, post_process_courses, read_html_tables,
+};
 use crate::{
-    models::{CourseFlags, CourseParseFormat, CourseRecord, CourseRecordType},
+    models::{CourseParseFormat, CourseRecord, CourseRecordType, Location, RecordStatus},
     services::CourseManager,
 };
-use chrono::{Duration, NaiveTime, Timelike, Weekday};
-use regex::Regex;
-use std::{cell::RefCell, collections::HashMap, rc::Rc, usize};
-
-const MIN_HOUR: u32 = 8;
-
-const COURSE_RECORD_REGEX: &str = r#"(?x)
-    <td>__([^_]+)__(?:[^<]*)<\/td>   # 1: Code
-    <td>([^<]*)<\/td>                # 2: Name
-    <td>([^<]*)<\/td>                # 3: Group
-    <td>([^<]*)<\/td>                # 4: Type
-    <td>([^<]*)<\/td>                # 5: Day
-    <td>([^<]*)<\/td>                # 6: From
-    <td>([^<]*)<\/td>                # 7: To
-    <td>([^<]*)<\/td>                # 8: Class size
-    <td>([^<]*)<\/td>                # 9: Enrolled
-    <td>([^<]*)<\/td>                # 10: Waiting
-    <td>([^<]*)<\/td>                # 11: Status
-    <td>([^<]*)<\/td>                # 12: Location
-"#;
-
-pub fn parse(course_manager: &mut CourseManager, data: &str) {
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+/// Rows missing any of these are rejected, the rest default to empty/zero
+const REQUIRED_PORTAL_COLUMNS: [CourseColumn; 6] = [
+    CourseColumn::Code,
+    CourseColumn::Group,
+    CourseColumn::Type,
+    CourseColumn::Day,
+    CourseColumn::From,
+    CourseColumn::To,
+];
+
+/// Registration portal html, raw or escaped
+/// Columns are found by their `<th>` titles so the portal can reorder or add them
+pub struct PortalParser;
+
+impl PortalParser {
+    pub const ID: &str = "portal";
+}
+
+impl CourseParser for PortalParser {
+    fn id(&self) -> &'static str {
+        Self::ID
+    }
+
+    fn detect(&self, input: &ParseInput) -> f32 {
+        let text = input.text();
+        if !text.contains("td") {
+            return 0.0;
+        }
+
+        let tables = read_html_tables(&normalize_portal_text(&text));
+        if tables.iter().any(|table| find_columns(table).is_some()) {
+            0.9
+        } else {
+            0.1
+        }
+    }
+
+    fn parse(
+        &self,
+        staging: &mut CourseManager,
+        input: &ParseInput,
+        options: &ParseOptions,
+    ) -> Result<ParseReport, ParseError> {
+        parse(staging, &input.text(), options)
+    }
+}
+
+pub fn parse(
+    course_manager: &mut CourseManager,
+    data: &str,
+    options: &ParseOptions,
+) -> Result<ParseReport, ParseError> {
     // Clear existing data
     course_manager.course_records.clear();
     course_manager.course_definitions.clear();
 
+    // Escaped script responses
+    let data = normalize_portal_text(data);
+
     // Parse new data
-    let re = Regex::new(COURSE_RECORD_REGEX).unwrap();
-    for c in re.captures_iter(data) {
-        let mut code = get_capture_value(&c, 1);
-
-        // Fixup name
-        let name_fixup = get_capture_value(&c, 2).replace("&amp;", "&");
-        let mut name = name_fixup.as_str();
-
-        // Parse group with potential irregular format
-        // Auto fixes code and name if needed
-        let group_str = get_capture_value(&c, 3);
-        let (group, parse_format) = parse_group(&mut code, &mut name, group_str);
-
-        // Everything else
-        let record_type: CourseRecordType = parse_direct(&c, 4);
-        let day: Weekday = parse_direct(&c, 5);
-        let mut from: NaiveTime = parse_direct(&c, 6);
-        let mut to: NaiveTime = parse_direct(&c, 7);
-        let class_size: i32 = parse_direct(&c, 8);
-        let enrolled: i32 = parse_direct(&c, 9);
-        let waiting: i32 = parse_direct(&c, 10);
-        let status = sanitize_str(get_capture_value(&c, 11));
-        let location = sanitize_str(get_capture_value(&c, 12));
-
-        // Validate timespans
-        fix_timespan(&mut from);
-        fix_timespan(&mut to);
-
-        // Get course def and register record
-        let course_definition_rc = course_manager.get_or_add_course_definition(code, name);
-
-        // Update course stats
-        match record_type {
-            CourseRecordType::Lecture => course_definition_rc.borrow_mut().lecture_count += 1,
-            CourseRecordType::Tutorial => course_definition_rc.borrow_mut().tutorial_count += 1,
-            CourseRecordType::None => {
-                panic!("Invalid course type {:?}", course_definition_rc.borrow())
+    let mut report = ParseReport::default();
+    let mut row_index = 0;
+    for table in read_html_tables(&data) {
+        let Some(columns) = find_columns(&table) else {
+            continue;
+        };
+
+        for row in &table.rows {
+            match parse_row(course_manager, row, &columns, row_index, options) {
+                Ok(()) => report.imported += 1,
+                Err(rejected_row) => report.rejected.push(rejected_row),
             }
+            row_index += 1;
         }
+    }
 
-        course_manager
-            .course_records
-            .push(Rc::new(RefCell::new(CourseRecord::new(
-                Rc::clone(&course_definition_rc),
-                group,
-                record_type,
-                day,
-                from,
-                to,
-                class_size,
-                enrolled,
-                waiting,
-                status,
-                location,
-                parse_format,
-            ))));
+    if report.imported == 0 {
+        return Err(ParseError::NoRecords(report));
     }
 
     // Sort courses and apply flags
     post_process_courses(course_manager);
+
+    Ok(report)
 }
 
-fn post_process_courses(course_manager: &mut CourseManager) {
-    // Sort course definitions by code
-    course_manager
-        .course_definitions
-        .sort_by(|a, b| a.borrow().code.cmp(&b.borrow().code));
-
-    // Sort records by day then time
-    course_manager.course_records.sort_by_key(|record| {
-        (
-            record.borrow().day.days_since(Weekday::Sat),
-            record.borrow().start_time,
-        )
-    });
-
-    // Flags
-    course_manager.course_definitions.iter().for_each(|def_rc| {
-        let mut lecture_group_map = HashMap::<i32, i32>::new();
-        let mut tutorial_group_map = HashMap::<i32, i32>::new();
-
-        // How many lecs/tuts for each group?
-        let mut count_groups = |record: &mut CourseRecord, record_type: CourseRecordType| {
-            if record.record_type != record_type {
-                return;
-            }
+/// Column of each header we know, None if the table isnt a course table
+/// Tables copied without their header row get the classic portal order, starting at the code cell
+fn find_columns(table: &HtmlTable) -> Option<HashMap<CourseColumn, usize>> {
+    let mut columns = HashMap::new();
+    for (idx, header) in table.headers.iter().enumerate() {
+        let column = CourseColumn::ALL
+            .into_iter()
+            .find(|column| column.to_string().eq_ignore_ascii_case(header));
+        if let Some(column) = column {
+            // First match wins
+            columns.entry(column).or_insert(idx);
+        }
+    }
 
-            let group_map: &mut HashMap<i32, i32>;
-            let mul_index: &mut i32;
+    if REQUIRED_PORTAL_COLUMNS
+        .iter()
+        .all(|column| columns.contains_key(column))
+    {
+        return Some(columns);
+    }
 
-            match record_type {
-                CourseRecordType::Lecture => {
-                    group_map = &mut lecture_group_map;
-                    mul_index = &mut record.mullec_index;
-                }
+    // Course code cells look like __CSE111__
+    let code_cell = table
+        .rows
+        .iter()
+        .find_map(|row| row.iter().position(|cell| cell.starts_with("__")))?;
+    let code_column = CourseColumn::ALL
+        .iter()
+        .position(|column| *column == CourseColumn::Code)
+        .unwrap_or_default();
+    Some(
+        CourseColumn::ALL
+            .into_iter()
+            .enumerate()
+            .filter_map(|(idx, column)| Some((column, (code_cell + idx).checked_sub(code_column)?)))
+            .collect(),
+    )
+}
 
-                CourseRecordType::Tutorial => {
-                    group_map = &mut tutorial_group_map;
-                    mul_index = &mut record.multut_index;
-                }
+fn parse_row(
+    course_manager: &mut CourseManager,
+    row: &[String],
+    columns: &HashMap<CourseColumn, usize>,
+    row_index: usize,
+    options: &ParseOptions,
+) -> Result<(), RejectedRow> {
+    let field = |column: CourseColumn| get_cell_value(row, columns, column);
+
+    // Fixup code, __CSE111__
+    let code_cell = field(CourseColumn::Code);
+    let mut code = code_cell
+        .split('_')
+        .map(str::trim)
+        .find(|part| !part.is_empty())
+        .unwrap_or_default();
+    let mut name = field(CourseColumn::Name);
+
+    // Would pass for irregular since every group cell contains it
+    if code.is_empty() {
+        return Err(RejectedRow {
+            row_index,
+            column: CourseColumn::Code,
+            raw: code_cell.to_owned(),
+            reason: "Missing course code".to_owned(),
+        });
+    }
 
-                CourseRecordType::None => return,
-            }
+    // Parse group with potential irregular format
+    // Auto fixes code and name if needed
+    let group_str = field(CourseColumn::Group);
+    let (group, parse_format) =
+        parse_group(&mut code, &mut name, group_str).map_err(|reason| RejectedRow {
+            row_index,
+            column: CourseColumn::Group,
+            raw: group_str.to_owned(),
+            reason,
+        })?;
+
+    // Everything else
+    let record_type: CourseRecordType = parse_direct(row, columns, CourseColumn::Type, row_index)?;
+    let day: Weekday = parse_direct(row, columns, CourseColumn::Day, row_index)?;
+    let mut from: NaiveTime = parse_direct(row, columns, CourseColumn::From, row_index)?;
+    let mut to: NaiveTime = parse_direct(row, columns, CourseColumn::To, row_index)?;
+    let class_size = parse_count(row, columns, CourseColumn::ClassSize, row_index)?;
+    let enrolled = parse_count(row, columns, CourseColumn::Enrolled, row_index)?;
+    let waiting = parse_count(row, columns, CourseColumn::Waiting, row_index)?;
+    let status = RecordStatus::parse(&sanitize_str(field(CourseColumn::Status)));
+    let location = Location::parse(&sanitize_str(field(CourseColumn::Location)));
+
+    // Neither is needed to schedule, bad values are dropped rather than the row
+    let id = sanitize_str(field(CourseColumn::Id)).parse::<u32>().ok();
+    let snapshot_time = parse_snapshot_time(field(CourseColumn::Date));
+
+    if record_type == CourseRecordType::None {
+        return Err(RejectedRow {
+            row_index,
+            column: CourseColumn::Type,
+            raw: field(CourseColumn::Type).to_owned(),
+            reason: "Invalid course type".to_owned(),
+        });
+    }
 
-            if !group_map.contains_key(&record.group) {
-                group_map.insert(record.group, 0);
-            }
+    // Validate timespans
+    fix_timespan(&mut from, options.afternoon_threshold_hour);
+    fix_timespan(&mut to, options.afternoon_threshold_hour);
 
-            // Increment
-            let new_count = group_map.get(&record.group).unwrap() + 1;
-            *group_map.get_mut(&record.group).unwrap() = new_count;
-            *mul_index = new_count;
-        };
+    // Get course def and register record
+    let course_definition_rc = course_manager.get_or_add_course_definition(code, name);
 
-        // Start counting
-        course_manager
-            .course_records
-            .iter_mut()
-            .filter(|record| {
-                record.borrow().course_definition.borrow().code == def_rc.borrow().code
-            })
-            .into_iter()
-            .for_each(|record| {
-                let record = &mut *record.borrow_mut();
-                count_groups(record, CourseRecordType::Lecture);
-                count_groups(record, CourseRecordType::Tutorial);
-            });
-
-        // Check for MultipleLectures
-        if lecture_group_map.iter().any(|(_, count)| *count > 1) {
-            def_rc.borrow_mut().flags |= CourseFlags::MultipleLectures;
-        }
+    // Update course stats
+    match record_type {
+        CourseRecordType::Lecture => course_definition_rc.borrow_mut().lecture_count += 1,
+        CourseRecordType::Tutorial => course_definition_rc.borrow_mut().tutorial_count += 1,
+        CourseRecordType::None => unreachable!(),
+    }
 
-        // Check for MultipleTutorials
-        if tutorial_group_map.iter().any(|(_, count)| *count > 1) {
-            def_rc.borrow_mut().flags |= CourseFlags::MultipleTutorials;
-        }
-    });
+    let mut record = CourseRecord::new(
+        Rc::clone(&course_definition_rc),
+        group,
+        record_type,
+        day,
+        from,
+        to,
+        class_size,
+        enrolled,
+        waiting,
+        status,
+        location,
+        parse_format,
+    );
+    record.id = id;
+    record.snapshot_time = snapshot_time;
+
+    course_manager
+        .course_records
+        .push(Rc::new(RefCell::new(record)));
+
+    Ok(())
 }
 
-fn fix_timespan(timespan: &mut NaiveTime) {
-    if timespan.hour() < MIN_HOUR {
-        *timespan = *timespan + Duration::hours(12);
+/// Portal times are 12h without am/pm
+pub fn fix_timespan(timespan: &mut NaiveTime, min_hour: u32) {
+    if timespan.hour() < min_hour {
+        *timespan += Duration::hours(12);
     }
 }
 
+/// Portal dates look like 1/21/2026 5:30:00 PM, exports and sheets tend to use iso
+pub fn parse_snapshot_time(raw: &str) -> Option<NaiveDateTime> {
+    let raw = raw.trim();
+    [
+        "%m/%d/%Y %I:%M:%S %p",
+        "%m/%d/%Y %I:%M %p",
+        "%m/%d/%Y %H:%M:%S",
+        "%m/%d/%Y %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(raw, format).ok())
+    .or_else(|| {
+        ["%m/%d/%Y", "%Y-%m-%d"]
+            .iter()
+            .find_map(|format| NaiveDate::parse_from_str(raw, format).ok())
+            .map(|date| date.and_time(NaiveTime::MIN))
+    })
+}
+
 fn parse_group<'a>(
     code: &mut &'a str,
     name: &mut &'a str,
     group_str: &'a str,
-) -> (i32, CourseParseFormat) {
+) -> Result<(i32, CourseParseFormat), String> {
     let group: i32;
     let mut parse_format: CourseParseFormat;
 
@@ -191,11 +280,11 @@ fn parse_group<'a>(
 
         // Determine format
         // x-yyyy
-        let sep = group_str.find('-').unwrap_or(usize::MAX);
-        if group_str.len() < 9 || sep == usize::MAX {
+        let sep = match group_str.find('-') {
             // Assuming group < 10
-            panic!("Irregular format is invalid");
-        }
+            Some(sep) if group_str.len() >= 9 => sep,
+            _ => return Err("Irregular format is invalid".to_owned()),
+        };
 
         // Which format?
         //	1-MTHS002
@@ -203,22 +292,22 @@ fn parse_group<'a>(
 
         // Okay
         // Find next
-        if let Some(sep2) = group_str[sep + 1..].rfind('-') {
+        if let Some(sep2) = group_str[(sep + 1)..].rfind('-').map(|idx| idx + sep + 1) {
             parse_format = CourseParseFormat::IrregularWithName;
             *code = &group_str[..sep];
-            *name = &group_str[(sep + 1)..sep2];
-            group = group_str[(sep + sep2)..].parse::<i32>().unwrap();
+            *name = group_str[(sep + 1)..sep2].trim();
+            group = parse_group_number(&group_str[(sep2 + 1)..])?;
         } else {
             //	5-MTHS003
             //	INTS203-G.1
-            match group_str[..sep].parse::<i32>() {
+            match group_str[..sep].trim().parse::<i32>() {
                 Ok(potential_group) => {
                     parse_format = CourseParseFormat::IrregularWithoutName;
                     group = potential_group;
                     *code = &group_str[(sep + 1)..];
 
                     //	5-5MTHS003
-                    if code.chars().next().unwrap().is_ascii_digit() {
+                    if code.starts_with(|ch: char| ch.is_ascii_digit()) {
                         parse_format = CourseParseFormat::IrregularWithoutNameGroupPrefixed;
                         *code = &(*code)[1..];
                     }
@@ -229,8 +318,8 @@ fn parse_group<'a>(
                     *code = &group_str[..sep];
 
                     // Crazy
-                    let group_part = group_str[..(sep + 1)].replace("G.", "");
-                    match group_part.parse::<i32>() {
+                    let group_part = group_str[(sep + 1)..].replace("G.", "");
+                    match group_part.trim().parse::<i32>() {
                         Ok(g) => group = g,
                         Err(_) => {
                             parse_format = CourseParseFormat::IrregularWithNameNoGroup;
@@ -241,28 +330,68 @@ fn parse_group<'a>(
                 }
             }
         }
+
+        if code.is_empty() {
+            return Err("Irregular format has no course code".to_owned());
+        }
     } else {
-        group = group_str.parse::<i32>().unwrap();
+        group = parse_group_number(group_str)?;
         parse_format = CourseParseFormat::Standard;
     }
 
-    (group, parse_format)
+    Ok((group, parse_format))
+}
+
+fn parse_group_number(data: &str) -> Result<i32, String> {
+    data.trim()
+        .parse::<i32>()
+        .map_err(|e| format!("Invalid group number: {}", e))
 }
 
 fn sanitize_str(data: &str) -> String {
     data.trim().replace("_", "")
 }
 
-fn get_capture_value<'a>(c: &'a regex::Captures<'a>, idx: usize) -> &'a str {
-    c.get(idx).unwrap().as_str().trim()
+fn get_cell_value<'a>(
+    row: &'a [String],
+    columns: &HashMap<CourseColumn, usize>,
+    column: CourseColumn,
+) -> &'a str {
+    columns
+        .get(&column)
+        .and_then(|idx| row.get(*idx))
+        .map_or("", |cell| cell.as_str())
 }
 
-fn parse_direct<T>(c: &regex::Captures, idx: usize) -> T
+fn parse_direct<T>(
+    row: &[String],
+    columns: &HashMap<CourseColumn, usize>,
+    column: CourseColumn,
+    row_index: usize,
+) -> Result<T, RejectedRow>
 where
     T: std::str::FromStr,
-    <T as std::str::FromStr>::Err: std::fmt::Debug,
+    <T as std::str::FromStr>::Err: std::fmt::Display,
 {
-    sanitize_str(get_capture_value(c, idx))
-        .parse::<T>()
-        .unwrap()
+    let raw = get_cell_value(row, columns, column);
+    sanitize_str(raw).parse::<T>().map_err(|e| RejectedRow {
+        row_index,
+        column,
+        raw: raw.to_owned(),
+        reason: e.to_string(),
+    })
+}
+
+/// Seat counts, blank means zero
+fn parse_count(
+    row: &[String],
+    columns: &HashMap<CourseColumn, usize>,
+    column: CourseColumn,
+    row_index: usize,
+) -> Result<i32, RejectedRow> {
+    if sanitize_str(get_cell_value(row, columns, column)).is_empty() {
+        return Ok(0);
+    }
+
+    parse_direct(row, columns, column, row_index)
 }
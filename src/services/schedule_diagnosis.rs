@@ -0,0 +1,322 @@
+use super::{
+    CourseManager, RecordConflict, ScheduleProblem, ScheduleSearch, ScheduleSlot, find_conflicts,
+};
+use std::{cmp::Reverse, rc::Rc};
+
+/// Bundles tried per feasibility check before we give up on it
+const DIAGNOSIS_STEPS: usize = 100_000;
+
+/// Up to this many courses every subset gets tried, 2^8 checks at most
+const MAX_EXHAUSTIVE_COURSES: usize = 8;
+
+/// What dropping a course would leave us with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropSuggestion {
+    pub course_code: String,
+    pub schedule_count: usize,
+
+    /// Hit the cap or the step budget, there are more
+    pub capped: bool,
+}
+
+/// Why there is no conflict free schedule
+#[derive(Debug, Clone)]
+pub struct ScheduleDiagnosis {
+    /// Slots the filter, pins or hard busy times left without any group
+    pub empty_slots: Vec<ScheduleSlot>,
+
+    /// Smallest set of selected courses that cant be taken together
+    pub conflicting_courses: Vec<String>,
+
+    /// Courses of `conflicting_courses` that leave some schedules when dropped, most first
+    pub suggestions: Vec<DropSuggestion>,
+}
+
+impl ScheduleDiagnosis {
+    /// Clashing records between the groups of `conflicting_courses`
+    /// Needs the records, so this one runs on the UI thread
+    pub fn clashes(
+        &self,
+        problem: &ScheduleProblem,
+        course_manager: &CourseManager,
+    ) -> Vec<RecordConflict> {
+        // Every group still in play for those courses
+        let records: Vec<_> = restricted(problem, &self.conflicting_courses)
+            .slots
+            .iter()
+            .flat_map(|slot| &slot.bundles)
+            .flat_map(|bundle| &bundle.record_indices)
+            .map(|&record_idx| Rc::clone(&course_manager.course_records[record_idx]))
+            .collect();
+
+        find_conflicts(&records)
+    }
+}
+
+/// Explains an infeasible problem, works off the same bundles the generator uses
+/// Suggestions count up to `max_schedules` like the generator
+pub fn diagnose_schedules(problem: &ScheduleProblem, max_schedules: usize) -> ScheduleDiagnosis {
+    let mut diagnoser = ScheduleDiagnoser::new(problem, max_schedules);
+    loop {
+        if let Some(diagnosis) = diagnoser.advance(problem, usize::MAX) {
+            return diagnosis;
+        }
+    }
+}
+
+/// `diagnose_schedules` split into bounded searches that can stop and resume anywhere
+/// Same deal as `ScheduleSearch`, lets it run on the generation worker or a chunk per frame
+pub struct ScheduleDiagnoser {
+    courses: Vec<String>,
+    empty_slots: Vec<ScheduleSlot>,
+    max_schedules: usize,
+    stage: DiagnosisStage,
+
+    /// Search running for the current stage
+    check: Option<DiagnosisCheck>,
+}
+
+enum DiagnosisStage {
+    /// Every subset of the courses, fewest courses first, the first infeasible one wins
+    Subsets {
+        masks: Vec<u32>,
+        next: usize,
+    },
+
+    /// Too many courses for that, drop them one by one while it stays infeasible
+    Shrinking {
+        core: Vec<String>,
+        next: usize,
+    },
+
+    /// Counting schedules without each course of the core
+    Suggesting {
+        core: Vec<String>,
+        next: usize,
+        suggestions: Vec<DropSuggestion>,
+    },
+
+    Finished,
+}
+
+/// One bounded search over some of the courses
+struct DiagnosisCheck {
+    courses: Vec<String>,
+    problem: ScheduleProblem,
+    search: ScheduleSearch,
+}
+
+impl DiagnosisCheck {
+    fn new(problem: &ScheduleProblem, courses: Vec<String>, max_schedules: usize) -> Self {
+        let problem = restricted(problem, &courses);
+        let search = ScheduleSearch::new(&problem, max_schedules);
+
+        Self {
+            courses,
+            problem,
+            search,
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.search.is_done() || self.search.steps() >= DIAGNOSIS_STEPS
+    }
+
+    /// Searched everything and found nothing, running out of steps proves nothing
+    fn is_infeasible(&self) -> bool {
+        self.search.is_done() && self.search.found() == 0
+    }
+}
+
+impl ScheduleDiagnoser {
+    pub fn new(problem: &ScheduleProblem, max_schedules: usize) -> Self {
+        let mut courses: Vec<String> = problem
+            .slots
+            .iter()
+            .map(|slot| slot.course_code.clone())
+            .collect();
+        courses.dedup();
+
+        let stage = if courses.len() <= MAX_EXHAUSTIVE_COURSES {
+            let mut masks: Vec<u32> = (1..1 << courses.len()).collect();
+            masks.sort_by_key(|mask| mask.count_ones());
+            DiagnosisStage::Subsets { masks, next: 0 }
+        } else {
+            DiagnosisStage::Shrinking {
+                core: courses.clone(),
+                next: 0,
+            }
+        };
+
+        Self {
+            empty_slots: problem
+                .slots
+                .iter()
+                .filter(|slot| slot.bundles.is_empty())
+                .cloned()
+                .collect(),
+            courses,
+            max_schedules,
+            stage,
+            check: None,
+        }
+    }
+
+    /// Tries up to `steps` more bundles, returns the diagnosis once it is done
+    /// `problem` has to be the one the diagnoser was made for
+    pub fn advance(
+        &mut self,
+        problem: &ScheduleProblem,
+        steps: usize,
+    ) -> Option<ScheduleDiagnosis> {
+        let mut steps_left = steps;
+
+        while !self.is_done() && steps_left > 0 {
+            let Some(check) = &mut self.check else {
+                if let Some(diagnosis) = self.next_check(problem) {
+                    return Some(diagnosis);
+                }
+                continue;
+            };
+
+            let steps_before = check.search.steps();
+            let budget = (DIAGNOSIS_STEPS - steps_before).min(steps_left);
+            check.search.advance(&check.problem, budget);
+            steps_left -= check.search.steps() - steps_before;
+
+            if check.is_done() {
+                let check = self.check.take().expect("check is running");
+                self.finish_check(check);
+            }
+        }
+
+        None
+    }
+
+    pub fn is_done(&self) -> bool {
+        matches!(self.stage, DiagnosisStage::Finished)
+    }
+
+    /// Starts the next search or moves on to the next stage, the diagnosis once out of both
+    fn next_check(&mut self, problem: &ScheduleProblem) -> Option<ScheduleDiagnosis> {
+        match &mut self.stage {
+            DiagnosisStage::Subsets { masks, next } => match masks.get(*next) {
+                Some(&mask) => {
+                    *next += 1;
+                    let subset = self
+                        .courses
+                        .iter()
+                        .enumerate()
+                        .filter(|(course_idx, _)| mask & (1 << course_idx) != 0)
+                        .map(|(_, course)| course.clone())
+                        .collect();
+                    self.check = Some(DiagnosisCheck::new(problem, subset, 1));
+                }
+
+                // Every subset has a schedule, blame all of them
+                None => self.stage = suggesting(self.courses.clone()),
+            },
+
+            DiagnosisStage::Shrinking { core, next } => match self.courses.get(*next) {
+                Some(course) => {
+                    *next += 1;
+                    let without: Vec<String> =
+                        core.iter().filter(|c| *c != course).cloned().collect();
+                    if !without.is_empty() {
+                        self.check = Some(DiagnosisCheck::new(problem, without, 1));
+                    }
+                }
+                None => self.stage = suggesting(std::mem::take(core)),
+            },
+
+            DiagnosisStage::Suggesting {
+                core,
+                next,
+                suggestions,
+            } => match core.get(*next) {
+                Some(dropped) => {
+                    *next += 1;
+                    let kept: Vec<String> = self
+                        .courses
+                        .iter()
+                        .filter(|course| *course != dropped)
+                        .cloned()
+                        .collect();
+                    let check = DiagnosisCheck::new(problem, kept, self.max_schedules);
+                    if !check.problem.is_empty() {
+                        self.check = Some(check);
+                    }
+                }
+                None => {
+                    suggestions.sort_by_key(|suggestion| Reverse(suggestion.schedule_count));
+                    let diagnosis = ScheduleDiagnosis {
+                        empty_slots: std::mem::take(&mut self.empty_slots),
+                        conflicting_courses: std::mem::take(core),
+                        suggestions: std::mem::take(suggestions),
+                    };
+
+                    self.stage = DiagnosisStage::Finished;
+                    return Some(diagnosis);
+                }
+            },
+
+            DiagnosisStage::Finished => {}
+        }
+
+        None
+    }
+
+    fn finish_check(&mut self, check: DiagnosisCheck) {
+        match &mut self.stage {
+            DiagnosisStage::Subsets { .. } => {
+                if check.is_infeasible() {
+                    self.stage = suggesting(check.courses);
+                }
+            }
+
+            DiagnosisStage::Shrinking { core, .. } => {
+                if check.is_infeasible() {
+                    *core = check.courses;
+                }
+            }
+
+            DiagnosisStage::Suggesting {
+                core,
+                next,
+                suggestions,
+            } => {
+                let schedule_count = check.search.found();
+                if schedule_count > 0 {
+                    suggestions.push(DropSuggestion {
+                        course_code: core[*next - 1].clone(),
+                        schedule_count,
+                        capped: !check.search.is_done() || schedule_count >= self.max_schedules,
+                    });
+                }
+            }
+
+            DiagnosisStage::Finished => {}
+        }
+    }
+}
+
+fn suggesting(core: Vec<String>) -> DiagnosisStage {
+    DiagnosisStage::Suggesting {
+        core,
+        next: 0,
+        suggestions: Vec::new(),
+    }
+}
+
+/// Only the slots of the given courses
+fn restricted(problem: &ScheduleProblem, courses: &[String]) -> ScheduleProblem {
+    ScheduleProblem {
+        slots: problem
+            .slots
+            .iter()
+            .filter(|slot| courses.contains(&slot.course_code))
+            .cloned()
+            .collect(),
+        blocked_times: Vec::new(),
+    }
+}
@@ -0,0 +1,437 @@
+use super::CourseManager;
+use crate::models::{
+    Availability, BlockedTime, CourseRecord, CourseRecordType, GroupFilter, Location, SavedGroup,
+    WeekOccupancy,
+};
+use chrono::{NaiveTime, Weekday};
+use std::{cell::RefCell, collections::BTreeMap, fmt, rc::Rc};
+
+/// Time slot of a single record, detached from the Rc graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Session {
+    pub day: Weekday,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+}
+
+impl Session {
+    pub fn from_record(record: &CourseRecord) -> Self {
+        Self {
+            day: record.day,
+            start_time: record.start_time,
+            end_time: record.end_time,
+        }
+    }
+
+    pub fn blocked_minutes(&self, blocked_time: &BlockedTime) -> i64 {
+        blocked_time.overlap_minutes(self.day, self.start_time, self.end_time)
+    }
+
+    /// Same day and intersecting [start_time, end_time)
+    pub fn overlaps(&self, other: &Session) -> bool {
+        self.day == other.day
+            && self.start_time < other.end_time
+            && other.start_time < self.end_time
+    }
+}
+
+/// Every record of one lecture/tutorial group, they can only be picked together
+#[derive(Debug, Clone)]
+pub struct GroupBundle {
+    pub course_code: String,
+    pub record_type: CourseRecordType,
+    pub group: i32,
+
+    /// Indices into `CourseManager::course_records`
+    pub record_indices: Vec<usize>,
+    pub sessions: Vec<Session>,
+
+    /// Where each of `sessions` takes place
+    pub locations: Vec<Location>,
+
+    /// `sessions` as a bitmask, built alongside them
+    pub occupancy: WeekOccupancy,
+
+    /// Worst of its records
+    pub availability: Availability,
+}
+
+impl GroupBundle {
+    pub fn overlaps(&self, other: &GroupBundle) -> bool {
+        if !self.occupancy.overlaps(&other.occupancy) {
+            return false;
+        }
+
+        // Times off the 5 minute grid got rounded, they might only touch
+        if self.occupancy.is_approximate() || other.occupancy.is_approximate() {
+            return self.sessions_overlap(other);
+        }
+
+        true
+    }
+
+    fn sessions_overlap(&self, other: &GroupBundle) -> bool {
+        self.sessions
+            .iter()
+            .any(|a| other.sessions.iter().any(|b| a.overlaps(b)))
+    }
+
+    pub fn is_blocked_by(&self, blocked_time: &BlockedTime) -> bool {
+        self.sessions
+            .iter()
+            .any(|session| session.blocked_minutes(blocked_time) > 0)
+    }
+}
+
+/// Why a group never made it into its slot
+#[derive(Debug, Clone, PartialEq)]
+pub enum LeftOutReason {
+    /// Another group of the slot is pinned
+    NotPinned(i32),
+
+    /// Skipped by the group filter
+    Filtered(Availability),
+
+    /// Overlaps a hard busy time
+    Blocked(BlockedTime),
+}
+
+impl fmt::Display for LeftOutReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LeftOutReason::NotPinned(group) => write!(f, "G{} is pinned", group),
+            LeftOutReason::Filtered(availability) => write!(f, "{}", availability),
+            LeftOutReason::Blocked(blocked_time) => write!(f, "during {}", blocked_time.label),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeftOutGroup {
+    pub group: i32,
+    pub reason: LeftOutReason,
+}
+
+/// A single pick we have to make, ie the lecture group of a course
+#[derive(Debug, Clone)]
+pub struct ScheduleSlot {
+    pub course_code: String,
+    pub record_type: CourseRecordType,
+    pub bundles: Vec<GroupBundle>,
+
+    /// Group the user locked, its the only bundle left
+    pub pinned_group: Option<i32>,
+
+    /// Groups taken out of `bundles` and why
+    pub left_out: Vec<LeftOutGroup>,
+}
+
+/// One bundle per slot, indices follow `ScheduleProblem::slots`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    pub choices: Vec<usize>,
+}
+
+impl Schedule {
+    pub fn bundles<'a>(
+        &'a self,
+        problem: &'a ScheduleProblem,
+    ) -> impl Iterator<Item = &'a GroupBundle> + 'a {
+        self.choices
+            .iter()
+            .zip(&problem.slots)
+            .map(|(&choice, slot)| &slot.bundles[choice])
+    }
+
+    /// Picked groups in slot order, survives regenerating the schedules
+    pub fn saved_groups(&self, problem: &ScheduleProblem) -> Vec<SavedGroup> {
+        self.bundles(problem)
+            .map(|bundle| SavedGroup {
+                code: bundle.course_code.clone(),
+                record_type: bundle.record_type,
+                group: bundle.group,
+            })
+            .collect()
+    }
+
+    /// Indices into `CourseManager::course_records`
+    pub fn record_indices(&self, problem: &ScheduleProblem) -> Vec<usize> {
+        self.bundles(problem)
+            .flat_map(|bundle| bundle.record_indices.iter().copied())
+            .collect()
+    }
+}
+
+pub struct ScheduleProblem {
+    pub slots: Vec<ScheduleSlot>,
+
+    /// Soft ones count against schedules, hard ones already removed the groups they overlap
+    pub blocked_times: Vec<BlockedTime>,
+}
+
+impl ScheduleProblem {
+    /// Builds the problem out of the selected courses
+    /// Pinned slots keep only their group, even if the filter or a hard block would reject it
+    /// Other slots lose the groups the filter rejects or a hard block overlaps
+    pub fn from_course_manager(
+        course_manager: &CourseManager,
+        group_filter: GroupFilter,
+        blocked_times: &[BlockedTime],
+    ) -> Self {
+        let records = course_manager
+            .course_records
+            .iter()
+            .enumerate()
+            .filter(|(_, record)| record.borrow().course_definition.borrow().selected);
+
+        let mut problem = Self::from_indexed_records(records);
+        for slot in &mut problem.slots {
+            let pinned_group = course_manager
+                .course_definitions
+                .iter()
+                .map(|def| def.borrow())
+                .find(|def| def.code == slot.course_code)
+                .and_then(|def| def.pinned_groups.get(&slot.record_type).copied());
+
+            let mut left_out = Vec::new();
+            slot.bundles.retain(|bundle| {
+                let reason = match pinned_group {
+                    Some(group) => {
+                        (bundle.group != group).then_some(LeftOutReason::NotPinned(group))
+                    }
+                    None if !group_filter.allows(bundle.availability) => {
+                        Some(LeftOutReason::Filtered(bundle.availability))
+                    }
+                    None => blocked_times
+                        .iter()
+                        .find(|blocked_time| {
+                            blocked_time.hard && bundle.is_blocked_by(blocked_time)
+                        })
+                        .map(|blocked_time| LeftOutReason::Blocked(blocked_time.clone())),
+                };
+
+                let Some(reason) = reason else {
+                    return true;
+                };
+
+                left_out.push(LeftOutGroup {
+                    group: bundle.group,
+                    reason,
+                });
+                false
+            });
+
+            slot.pinned_group = pinned_group;
+            slot.left_out = left_out;
+        }
+
+        problem.blocked_times = blocked_times.to_vec();
+        problem
+    }
+
+    /// Builds the problem out of (index, record) pairs
+    pub fn from_indexed_records<'a>(
+        records: impl Iterator<Item = (usize, &'a Rc<RefCell<CourseRecord>>)>,
+    ) -> Self {
+        // (code, type) -> group -> bundle
+        // Multi lecture/tutorial groups (mullec/multut) land in the same bundle
+        let mut slot_map =
+            BTreeMap::<(String, CourseRecordType), BTreeMap<i32, GroupBundle>>::new();
+
+        for (index, record_rc) in records {
+            let record = record_rc.borrow();
+            if record.record_type == CourseRecordType::None {
+                continue;
+            }
+
+            let code = record.course_definition.borrow().code.clone();
+            let bundle = slot_map
+                .entry((code.clone(), record.record_type))
+                .or_default()
+                .entry(record.group)
+                .or_insert_with(|| GroupBundle {
+                    course_code: code,
+                    record_type: record.record_type,
+                    group: record.group,
+                    record_indices: Vec::new(),
+                    sessions: Vec::new(),
+                    locations: Vec::new(),
+                    occupancy: WeekOccupancy::new(),
+                    availability: record.availability(),
+                });
+
+            bundle.record_indices.push(index);
+            bundle.sessions.push(Session::from_record(&record));
+            bundle.locations.push(record.location.clone());
+            bundle
+                .occupancy
+                .insert(record.day, record.start_time, record.end_time);
+            bundle.availability = bundle.availability.worst(record.availability());
+        }
+
+        let slots = slot_map
+            .into_values()
+            .map(|bundles| {
+                let bundles: Vec<GroupBundle> = bundles.into_values().collect();
+                ScheduleSlot {
+                    course_code: bundles[0].course_code.clone(),
+                    record_type: bundles[0].record_type,
+                    bundles,
+                    pinned_group: None,
+                    left_out: Vec::new(),
+                }
+            })
+            .collect();
+
+        Self {
+            slots,
+            blocked_times: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Enumerates conflict free schedules, stops after `max_schedules`
+    pub fn generate(&self, max_schedules: usize) -> Vec<Schedule> {
+        let mut search = ScheduleSearch::new(self, max_schedules);
+        search.advance(self, usize::MAX)
+    }
+}
+
+/// Depth first search over the slots that can stop and resume anywhere
+/// Lets generation run a chunk per frame or report progress from a thread
+#[derive(Debug, Clone)]
+pub struct ScheduleSearch {
+    /// Slot indices, most constrained first, prunes way earlier
+    order: Vec<usize>,
+    choices: Vec<usize>,
+
+    /// Next bundle to try at each depth
+    next_bundles: Vec<usize>,
+
+    /// Everything picked above each depth
+    occupied: Vec<WeekOccupancy>,
+    depth: usize,
+
+    /// Bundles tried so far
+    steps: usize,
+    found: usize,
+    max_schedules: usize,
+    done: bool,
+}
+
+impl ScheduleSearch {
+    pub fn new(problem: &ScheduleProblem, max_schedules: usize) -> Self {
+        let mut order: Vec<usize> = (0..problem.slots.len()).collect();
+        order.sort_by_key(|&slot_idx| problem.slots[slot_idx].bundles.len());
+
+        Self {
+            choices: vec![0; order.len()],
+            next_bundles: vec![0; order.len()],
+            occupied: vec![WeekOccupancy::new(); order.len() + 1],
+            order,
+            depth: 0,
+            steps: 0,
+            found: 0,
+            max_schedules,
+            done: problem.slots.is_empty() || max_schedules == 0,
+        }
+    }
+
+    /// Tries up to `steps` more bundles, returns the schedules found meanwhile
+    /// `problem` has to be the one the search was made for
+    pub fn advance(&mut self, problem: &ScheduleProblem, steps: usize) -> Vec<Schedule> {
+        let mut schedules = Vec::new();
+        let mut steps_left = steps;
+
+        while !self.done && steps_left > 0 {
+            // Every slot picked
+            if self.depth == self.order.len() {
+                schedules.push(Schedule {
+                    choices: self.choices.clone(),
+                });
+                self.found += 1;
+                self.done = self.found >= self.max_schedules;
+                self.depth -= 1;
+                continue;
+            }
+
+            let slot_idx = self.order[self.depth];
+            let bundle_idx = self.next_bundles[self.depth];
+
+            // Out of bundles here, back up a slot
+            let Some(bundle) = problem.slots[slot_idx].bundles.get(bundle_idx) else {
+                self.next_bundles[self.depth] = 0;
+                match self.depth.checked_sub(1) {
+                    Some(depth) => self.depth = depth,
+                    None => self.done = true,
+                }
+                continue;
+            };
+
+            self.next_bundles[self.depth] += 1;
+            self.steps += 1;
+            steps_left -= 1;
+
+            // Does it clash with anything we picked so far?
+            // `occupied` has every pick, only rounded times need a closer look
+            let occupied = &self.occupied[self.depth];
+            if bundle.occupancy.overlaps(occupied) {
+                let exact = !bundle.occupancy.is_approximate() && !occupied.is_approximate();
+                let clashes = exact
+                    || self.order[..self.depth].iter().any(|&picked_slot_idx| {
+                        let picked =
+                            &problem.slots[picked_slot_idx].bundles[self.choices[picked_slot_idx]];
+                        picked.overlaps(bundle)
+                    });
+
+                if clashes {
+                    continue;
+                }
+            }
+
+            let mut next_occupied = *occupied;
+            next_occupied.union_with(&bundle.occupancy);
+
+            self.choices[slot_idx] = bundle_idx;
+            self.occupied[self.depth + 1] = next_occupied;
+            self.depth += 1;
+        }
+
+        schedules
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Bundles tried so far, what `advance` counts its steps in
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Schedules found so far
+    pub fn found(&self) -> usize {
+        self.found
+    }
+
+    /// Rough share of the search already covered, 0 to 1
+    /// Treats every branch as the same size so it jumps around on lopsided problems
+    pub fn progress(&self, problem: &ScheduleProblem) -> f32 {
+        if self.done {
+            return 1.0;
+        }
+
+        let mut progress = 0.0;
+        let mut branch_size = 1.0;
+        for (&slot_idx, &next_bundle) in self.order.iter().zip(&self.next_bundles) {
+            let bundle_count = problem.slots[slot_idx].bundles.len().max(1) as f32;
+            branch_size /= bundle_count;
+            progress += next_bundle.saturating_sub(1) as f32 * branch_size;
+        }
+
+        progress.min(1.0)
+    }
+}
@@ -0,0 +1,223 @@
+use super::{Schedule, ScheduleProblem, Session, tight_break};
+use crate::models::{CampusMap, Location, OrderedWeekday, SchedulePreferences};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BinaryHeap},
+};
+
+/// Breakdown of how a schedule did against the preferences
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScheduleScore {
+    pub campus_days: u32,
+    pub early_sessions: u32,
+    pub gap_minutes: i64,
+    pub campus_minutes: i64,
+    pub free_day_taken: bool,
+
+    /// Breaks too short to get to the next session
+    pub tight_transfers: u32,
+
+    /// Class time inside soft blocked times
+    pub blocked_minutes: i64,
+
+    /// Weighted sum of the above, lower is better
+    pub penalty: f32,
+}
+
+pub fn score_schedule(
+    problem: &ScheduleProblem,
+    schedule: &Schedule,
+    preferences: &SchedulePreferences,
+    campus_map: &CampusMap,
+) -> ScheduleScore {
+    // Sessions per day, sorted by start
+    let mut day_map = BTreeMap::<OrderedWeekday, Vec<(Session, &Location)>>::new();
+    schedule
+        .bundles(problem)
+        .flat_map(|bundle| bundle.sessions.iter().zip(&bundle.locations))
+        .for_each(|(session, location)| {
+            day_map
+                .entry(session.day.into())
+                .or_default()
+                .push((*session, location))
+        });
+
+    let mut score = ScheduleScore {
+        campus_days: day_map.len() as u32,
+        ..Default::default()
+    };
+
+    for (day, sessions) in day_map.iter_mut() {
+        sessions.sort_by_key(|(session, _)| session.start_time);
+
+        if preferences
+            .free_day
+            .is_some_and(|free_day| OrderedWeekday::from(free_day) == *day)
+        {
+            score.free_day_taken = true;
+        }
+
+        score.blocked_minutes += sessions
+            .iter()
+            .flat_map(|(session, _)| {
+                problem
+                    .blocked_times
+                    .iter()
+                    .filter(|blocked_time| !blocked_time.hard)
+                    .map(|blocked_time| session.blocked_minutes(blocked_time))
+            })
+            .sum::<i64>();
+
+        if let Some(earliest_start) = preferences.earliest_start {
+            score.early_sessions += sessions
+                .iter()
+                .filter(|(session, _)| session.start_time < earliest_start)
+                .count() as u32;
+        }
+
+        // Idle time between consecutive sessions
+        let mut last_end = sessions[0].0.end_time;
+        for (session, _) in &sessions[1..] {
+            if session.start_time > last_end {
+                score.gap_minutes += (session.start_time - last_end).num_minutes();
+            }
+
+            last_end = last_end.max(session.end_time);
+        }
+
+        score.campus_minutes += (last_end - sessions[0].0.start_time).num_minutes();
+
+        // Rushing across campus between back to back sessions
+        score.tight_transfers += sessions
+            .windows(2)
+            .filter(|pair| {
+                let [(from, from_location), (to, to_location)] = pair else {
+                    return false;
+                };
+                let needed_minutes = campus_map.travel_minutes(from_location, to_location);
+                tight_break(from.end_time, to.start_time, needed_minutes).is_some()
+            })
+            .count() as u32;
+    }
+
+    score.penalty = score.campus_days as f32 * preferences.campus_days_weight
+        + score.early_sessions as f32 * preferences.early_start_weight
+        + score.gap_minutes as f32 / 60.0 * preferences.gaps_weight
+        + score.campus_minutes as f32 / 60.0 * preferences.compactness_weight
+        + score.tight_transfers as f32 * preferences.tight_transfer_weight
+        + score.blocked_minutes as f32 / 60.0 * preferences.blocked_time_weight
+        + if score.free_day_taken {
+            preferences.free_day_weight
+        } else {
+            0.0
+        };
+
+    score
+}
+
+/// Sorts schedules so the best ones come first, ties keep their generation order
+pub fn rank_schedules(
+    problem: &ScheduleProblem,
+    schedules: &mut Vec<Schedule>,
+    preferences: &SchedulePreferences,
+    campus_map: &CampusMap,
+) {
+    let mut top = TopSchedules::new(schedules.len());
+    schedules.drain(..).for_each(|schedule| {
+        let penalty = score_schedule(problem, &schedule, preferences, campus_map).penalty;
+        top.push(penalty, schedule);
+    });
+
+    *schedules = top.ranked();
+}
+
+/// Lowest penalty schedules out of everything pushed, at most `capacity` of them
+/// Max heap on the penalty so the worst kept one is always on top to get evicted
+#[derive(Debug, Clone)]
+pub struct TopSchedules {
+    capacity: usize,
+    heap: BinaryHeap<RankedSchedule>,
+
+    /// Pushed so far, kept or not
+    seen: usize,
+}
+
+impl TopSchedules {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            heap: BinaryHeap::with_capacity(capacity),
+            seen: 0,
+        }
+    }
+
+    /// Returns true if it made the cut
+    pub fn push(&mut self, penalty: f32, schedule: Schedule) -> bool {
+        let ranked = RankedSchedule {
+            penalty,
+            order: self.seen,
+            schedule,
+        };
+        self.seen += 1;
+
+        if self.heap.len() < self.capacity {
+            self.heap.push(ranked);
+            return true;
+        }
+
+        match self.heap.peek_mut() {
+            Some(mut worst) if ranked < *worst => {
+                *worst = ranked;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn seen(&self) -> usize {
+        self.seen
+    }
+
+    /// Best first, ties keep the order they were pushed in
+    pub fn ranked(&self) -> Vec<Schedule> {
+        self.heap
+            .clone()
+            .into_sorted_vec()
+            .into_iter()
+            .map(|ranked| ranked.schedule)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RankedSchedule {
+    penalty: f32,
+    order: usize,
+    schedule: Schedule,
+}
+
+impl Ord for RankedSchedule {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.penalty
+            .total_cmp(&other.penalty)
+            .then(self.order.cmp(&other.order))
+    }
+}
+
+impl PartialOrd for RankedSchedule {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for RankedSchedule {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for RankedSchedule {}
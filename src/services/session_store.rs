@@ -0,0 +1,89 @@
+use super::CourseManager;
+use crate::models::{SavedGroup, SavedSelection, SavedSession};
+
+/// Fingerprint of the offered groups of a dataset
+/// Seats and rooms change hourly so only codes, types and groups go in,
+/// re-importing the same term matches while a new term doesnt
+pub fn dataset_hash(course_manager: &CourseManager) -> String {
+    let mut keys: Vec<String> = course_manager
+        .course_records
+        .iter()
+        .map(|record| {
+            let record = record.borrow();
+            format!(
+                "{}|{:?}|{}",
+                record.course_definition.borrow().code,
+                record.record_type,
+                record.group
+            )
+        })
+        .collect();
+
+    keys.sort();
+    keys.dedup();
+
+    // FNV-1a, stable across builds unlike DefaultHasher
+    let hash = keys
+        .iter()
+        .flat_map(|key| key.bytes().chain(std::iter::once(b'\n')))
+        .fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+
+    format!("{:016x}", hash)
+}
+
+pub fn capture_selection(
+    course_manager: &CourseManager,
+    chosen_groups: &[SavedGroup],
+) -> SavedSelection {
+    SavedSelection {
+        selected_codes: course_manager
+            .course_definitions
+            .iter()
+            .filter(|def| def.borrow().selected)
+            .map(|def| def.borrow().code.clone())
+            .collect(),
+        chosen_groups: chosen_groups.to_vec(),
+        pinned_groups: course_manager
+            .course_definitions
+            .iter()
+            .flat_map(|def| {
+                let def = def.borrow();
+                def.pinned_groups
+                    .iter()
+                    .map(|(&record_type, &group)| SavedGroup {
+                        code: def.code.clone(),
+                        record_type,
+                        group,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect(),
+        last_used: chrono::Utc::now().timestamp(),
+    }
+}
+
+/// Re-selects saved courses and pins, unknown codes are ignored
+pub fn apply_selection(course_manager: &CourseManager, selection: &SavedSelection) {
+    course_manager.course_definitions.iter().for_each(|def| {
+        let mut def = def.borrow_mut();
+        def.selected = selection.selected_codes.contains(&def.code);
+        def.pinned_groups = selection
+            .pinned_groups
+            .iter()
+            .filter(|pinned| pinned.code == def.code)
+            .map(|pinned| (pinned.record_type, pinned.group))
+            .collect();
+    });
+}
+
+pub fn load_session(storage: Option<&dyn eframe::Storage>) -> SavedSession {
+    storage
+        .and_then(|storage| eframe::get_value(storage, SavedSession::STORAGE_KEY))
+        .unwrap_or_default()
+}
+
+pub fn save_session(storage: &mut dyn eframe::Storage, session: &SavedSession) {
+    eframe::set_value(storage, SavedSession::STORAGE_KEY, session);
+}
@@ -0,0 +1,225 @@
+use super::CourseManager;
+use crate::models::{CourseRecord, CourseRecordType, OrderedWeekday, RecordKey, RecordStatus};
+use chrono::{NaiveDateTime, NaiveTime, Weekday};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+/// When a group meets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MeetingTime {
+    pub day: Weekday,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+}
+
+impl MeetingTime {
+    fn of(record: &CourseRecord) -> Self {
+        Self {
+            day: record.day,
+            start_time: record.start_time,
+            end_time: record.end_time,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordChange {
+    Added,
+    Removed,
+    Rescheduled {
+        old: MeetingTime,
+        new: MeetingTime,
+    },
+
+    /// Readable room labels
+    Relocated {
+        old: String,
+        new: String,
+    },
+    StatusChanged {
+        old: RecordStatus,
+        new: RecordStatus,
+    },
+
+    /// New minus old
+    SeatsChanged {
+        enrolled: i32,
+        waiting: i32,
+    },
+}
+
+/// Everything that changed about one record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordDiff {
+    pub code: String,
+    pub name: String,
+    pub record_type: CourseRecordType,
+    pub group: i32,
+
+    /// As of the newer snapshot, or the older one for removed records
+    pub meeting: MeetingTime,
+    pub changes: Vec<RecordChange>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotDiff {
+    pub old_snapshot_time: Option<NaiveDateTime>,
+    pub new_snapshot_time: Option<NaiveDateTime>,
+
+    /// Changed records only, by code, type, group then day
+    pub records: Vec<RecordDiff>,
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn for_courses(&self, codes: &[String]) -> Vec<&RecordDiff> {
+        self.records
+            .iter()
+            .filter(|diff| codes.contains(&diff.code))
+            .collect()
+    }
+}
+
+/// Share of course codes two snapshots need in common to count as the same dataset
+const SAME_DATASET_MIN_OVERLAP: f32 = 0.5;
+
+/// Whether `new` is a later snapshot of the data in `old`, not another term or faculty
+/// Diffing unrelated data would only list every record as removed and added
+pub fn is_same_dataset(old: &CourseManager, new: &CourseManager) -> bool {
+    let codes = |course_manager: &CourseManager| -> HashSet<String> {
+        course_manager
+            .course_definitions
+            .iter()
+            .map(|definition| definition.borrow().code.clone())
+            .collect()
+    };
+    let (old_codes, new_codes) = (codes(old), codes(new));
+
+    let union = old_codes.union(&new_codes).count();
+    if union == 0 {
+        return false;
+    }
+
+    let shared = old_codes.intersection(&new_codes).count();
+    shared as f32 / union as f32 >= SAME_DATASET_MIN_OVERLAP
+}
+
+/// Matches records by portal id when both snapshots have them, by group and slot otherwise
+/// Without ids a rescheduled group shows up as removed and added
+pub fn diff_snapshots(old: &CourseManager, new: &CourseManager) -> SnapshotDiff {
+    let has_ids = |course_manager: &CourseManager| {
+        course_manager
+            .course_records
+            .iter()
+            .all(|record| record.borrow().id.is_some())
+    };
+    let use_ids = has_ids(old) && has_ids(new);
+    let key = |record: &CourseRecord| {
+        if use_ids {
+            record.key()
+        } else {
+            record.slot_key()
+        }
+    };
+
+    // Records sharing a key, ie a group split over two rooms, get matched in order
+    let mut old_records: HashMap<RecordKey, Vec<&Rc<RefCell<CourseRecord>>>> = HashMap::new();
+    for record in &old.course_records {
+        old_records
+            .entry(key(&record.borrow()))
+            .or_default()
+            .push(record);
+    }
+
+    let mut records = Vec::new();
+    for new_record in &new.course_records {
+        let new_record = new_record.borrow();
+        let old_record = old_records
+            .get_mut(&key(&new_record))
+            .filter(|old_records| !old_records.is_empty())
+            .map(|old_records| old_records.remove(0));
+
+        let changes = match old_record {
+            Some(old_record) => record_changes(&old_record.borrow(), &new_record),
+            None => vec![RecordChange::Added],
+        };
+
+        if !changes.is_empty() {
+            records.push(record_diff(&new_record, changes));
+        }
+    }
+
+    records.extend(
+        old_records
+            .into_values()
+            .flatten()
+            .map(|old_record| record_diff(&old_record.borrow(), vec![RecordChange::Removed])),
+    );
+
+    records.sort_by_cached_key(|diff| {
+        (
+            diff.code.clone(),
+            diff.record_type,
+            diff.group,
+            OrderedWeekday::from(diff.meeting.day),
+            diff.meeting.start_time,
+        )
+    });
+
+    SnapshotDiff {
+        old_snapshot_time: old.snapshot_time(),
+        new_snapshot_time: new.snapshot_time(),
+        records,
+    }
+}
+
+fn record_changes(old: &CourseRecord, new: &CourseRecord) -> Vec<RecordChange> {
+    let mut changes = Vec::new();
+
+    let (old_meeting, new_meeting) = (MeetingTime::of(old), MeetingTime::of(new));
+    if old_meeting != new_meeting {
+        changes.push(RecordChange::Rescheduled {
+            old: old_meeting,
+            new: new_meeting,
+        });
+    }
+
+    if old.location != new.location {
+        changes.push(RecordChange::Relocated {
+            old: old.location.to_string(),
+            new: new.location.to_string(),
+        });
+    }
+
+    if old.status != new.status {
+        changes.push(RecordChange::StatusChanged {
+            old: old.status.clone(),
+            new: new.status.clone(),
+        });
+    }
+
+    let (enrolled, waiting) = (new.enrolled - old.enrolled, new.waiting - old.waiting);
+    if enrolled != 0 || waiting != 0 {
+        changes.push(RecordChange::SeatsChanged { enrolled, waiting });
+    }
+
+    changes
+}
+
+fn record_diff(record: &CourseRecord, changes: Vec<RecordChange>) -> RecordDiff {
+    let definition = record.course_definition.borrow();
+    RecordDiff {
+        code: definition.code.clone(),
+        name: definition.name.clone(),
+        record_type: record.record_type,
+        group: record.group,
+        meeting: MeetingTime::of(record),
+        changes,
+    }
+}
@@ -0,0 +1,100 @@
+use super::records_are_alternatives;
+use crate::models::{CampusMap, CourseRecord, OrderedWeekday, TimeFormat};
+use chrono::NaiveTime;
+use std::{cell::RefCell, rc::Rc};
+
+/// Back to back records too far apart to make it in time
+#[derive(Debug, Clone)]
+pub struct TightTransfer {
+    pub from: Rc<RefCell<CourseRecord>>,
+    pub to: Rc<RefCell<CourseRecord>>,
+    pub break_minutes: i64,
+    pub needed_minutes: u32,
+}
+
+impl TightTransfer {
+    pub fn involves(&self, record: &Rc<RefCell<CourseRecord>>) -> bool {
+        Rc::ptr_eq(&self.from, record) || Rc::ptr_eq(&self.to, record)
+    }
+
+    pub fn describe(&self, time_format: TimeFormat) -> String {
+        let from = self.from.borrow();
+        let to = self.to.borrow();
+
+        format!(
+            "{} min from {} {:?} G{} ({}) to {} {:?} G{} ({}) on {} at {}, about {} min needed",
+            self.break_minutes,
+            from.course_definition.borrow().code,
+            from.record_type,
+            from.group,
+            from.location,
+            to.course_definition.borrow().code,
+            to.record_type,
+            to.group,
+            to.location,
+            OrderedWeekday::from(from.day),
+            time_format.format(from.end_time),
+            self.needed_minutes,
+        )
+    }
+}
+
+/// Minutes of break when the next session starts too soon to get there, None if its fine
+pub fn tight_break(
+    end_time: NaiveTime,
+    next_start_time: NaiveTime,
+    needed_minutes: u32,
+) -> Option<i64> {
+    let break_minutes = (next_start_time - end_time).num_minutes();
+    (break_minutes >= 0 && break_minutes < needed_minutes as i64).then_some(break_minutes)
+}
+
+/// Every record followed by one it cant reach in time
+/// Alternative groups arent attended together so they never follow each other
+pub fn find_tight_transfers(
+    records: &[Rc<RefCell<CourseRecord>>],
+    campus_map: &CampusMap,
+) -> Vec<TightTransfer> {
+    let mut sorted: Vec<&Rc<RefCell<CourseRecord>>> = records.iter().collect();
+    sorted.sort_by_key(|record| {
+        let record = record.borrow();
+        (OrderedWeekday::from(record.day), record.start_time)
+    });
+
+    let mut transfers = Vec::new();
+    for (i, from_rc) in sorted.iter().enumerate() {
+        let from = from_rc.borrow();
+        let followers: Vec<&Rc<RefCell<CourseRecord>>> = sorted[(i + 1)..]
+            .iter()
+            .take_while(|next| next.borrow().day == from.day)
+            .filter(|next| {
+                let next = next.borrow();
+                next.start_time >= from.end_time && !records_are_alternatives(&from, &next)
+            })
+            .copied()
+            .collect();
+
+        // Only the sessions right after this one, sorted so they come first
+        let Some(next_start_time) = followers.first().map(|next| next.borrow().start_time) else {
+            continue;
+        };
+
+        for to_rc in followers
+            .into_iter()
+            .take_while(|next| next.borrow().start_time == next_start_time)
+        {
+            let to = to_rc.borrow();
+            let needed_minutes = campus_map.travel_minutes(&from.location, &to.location);
+            if let Some(break_minutes) = tight_break(from.end_time, to.start_time, needed_minutes) {
+                transfers.push(TightTransfer {
+                    from: Rc::clone(from_rc),
+                    to: Rc::clone(to_rc),
+                    break_minutes,
+                    needed_minutes,
+                });
+            }
+        }
+    }
+
+    transfers
+}
@@ -0,0 +1,135 @@
+use std::{cell::RefCell, rc::Rc};
+
+/// A file the user gave us, read in full
+pub struct PickedFile {
+    pub name: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Filled once the user picks a file, stays empty if they cancel
+pub type PendingFile = Rc<RefCell<Option<PickedFile>>>;
+
+/// Blocking file picker on desktop, the dialog is modal anyway
+#[cfg(not(target_arch = "wasm32"))]
+pub fn pick_file(_ctx: &egui::Context, filter_name: &str, extensions: &[&str]) -> PendingFile {
+    let picked = rfd::FileDialog::new()
+        .add_filter(filter_name, extensions)
+        .pick_file()
+        .and_then(|path| read_file(&path));
+
+    Rc::new(RefCell::new(picked))
+}
+
+/// Async file picker on web, repaints once the file is read
+#[cfg(target_arch = "wasm32")]
+pub fn pick_file(ctx: &egui::Context, filter_name: &str, extensions: &[&str]) -> PendingFile {
+    let pending: PendingFile = Rc::new(RefCell::new(None));
+    let dialog = rfd::AsyncFileDialog::new().add_filter(filter_name, extensions);
+
+    let pending_clone = Rc::clone(&pending);
+    let ctx = ctx.clone();
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Some(handle) = dialog.pick_file().await {
+            *pending_clone.borrow_mut() = Some(PickedFile {
+                name: handle.file_name(),
+                bytes: handle.read().await,
+            });
+            ctx.request_repaint();
+        }
+    });
+
+    pending
+}
+
+/// Asks where to put the file then writes it, cancelling is not an error
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_file(
+    file_name: &str,
+    filter_name: &str,
+    extensions: &[&str],
+    bytes: &[u8],
+) -> Result<(), String> {
+    let Some(path) = rfd::FileDialog::new()
+        .set_file_name(file_name)
+        .add_filter(filter_name, extensions)
+        .save_file()
+    else {
+        return Ok(());
+    };
+
+    std::fs::write(&path, bytes)
+        .map_err(|err| format!("Failed to write {}: {}", path.display(), err))
+}
+
+/// Browsers dont let us pick a location, hand it over as a download instead
+#[cfg(target_arch = "wasm32")]
+pub fn save_file(
+    file_name: &str,
+    _filter_name: &str,
+    _extensions: &[&str],
+    bytes: &[u8],
+) -> Result<(), String> {
+    use wasm_bindgen::JsCast;
+
+    let js_err = |err: wasm_bindgen::JsValue| format!("Failed to save {}: {:?}", file_name, err);
+
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+    let blob = web_sys::Blob::new_with_u8_array_sequence(&parts).map_err(js_err)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(js_err)?;
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| format!("Failed to save {}: no document", file_name))?;
+    let anchor = document
+        .create_element("a")
+        .map_err(js_err)?
+        .dyn_into::<web_sys::HtmlAnchorElement>()
+        .map_err(|element| js_err(element.into()))?;
+
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+
+    web_sys::Url::revoke_object_url(&url).map_err(js_err)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_file(path: &std::path::Path) -> Option<PickedFile> {
+    match std::fs::read(path) {
+        Ok(bytes) => Some(PickedFile {
+            name: path.file_name().map_or(path.display().to_string(), |name| {
+                name.to_string_lossy().into_owned()
+            }),
+            bytes,
+        }),
+        Err(err) => {
+            super::log(&format!("Failed to read {}: {}", path.display(), err));
+            None
+        }
+    }
+}
+
+/// Files dropped on the window this frame
+pub fn take_dropped_files(ctx: &egui::Context) -> Vec<PickedFile> {
+    let dropped_files = ctx.input_mut(|i| std::mem::take(&mut i.raw.dropped_files));
+
+    dropped_files
+        .into_iter()
+        .filter_map(|file| {
+            // Web hands us bytes, desktop hands us a path
+            if let Some(bytes) = file.bytes {
+                return Some(PickedFile {
+                    name: file.name,
+                    bytes: bytes.to_vec(),
+                });
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some(path) = file.path {
+                return read_file(&path);
+            }
+
+            None
+        })
+        .collect()
+}
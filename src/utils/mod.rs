@@ -1,2 +1,7 @@
+mod file_dialog;
 mod logger;
+mod screenshot;
+
+pub use file_dialog::*;
 pub use logger::*;
+pub use screenshot::*;
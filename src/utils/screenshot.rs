@@ -0,0 +1,94 @@
+use egui::{ColorImage, Id, Rect, UserData, ViewportCommand};
+use std::sync::Arc;
+
+/// Tags our screenshot requests, carries the area to keep
+struct ScreenshotCrop {
+    crop: Rect,
+
+    /// Of the captured frame, the zoom may be back to normal by the time the image arrives
+    pixels_per_point: f32,
+
+    /// Zoom from before we shrank the ui to fit the capture
+    restore_zoom: Option<f32>,
+}
+
+/// Where the zoom from before a capture waits for the capture to come back
+fn restore_zoom_id() -> Id {
+    Id::new("screenshot_restore_zoom")
+}
+
+/// Captures the frame currently being drawn, cropped to `crop` (in points)
+/// A `crop` reaching past the window zooms the ui out instead, returns false then so the
+/// caller lays it out again and asks next frame, the zoom comes back with the capture
+pub fn request_screenshot(ctx: &egui::Context, crop: Rect) -> bool {
+    let window = ctx.content_rect();
+    let restore_zoom = ctx.data(|data| data.get_temp::<f32>(restore_zoom_id()));
+
+    // Only zoom out once, whatever still sticks out after that is cut off
+    if !window.contains_rect(crop) && restore_zoom.is_none() {
+        let fit = (window.max.x / crop.max.x).min(window.max.y / crop.max.y);
+        let zoom = ctx.zoom_factor();
+
+        ctx.data_mut(|data| data.insert_temp(restore_zoom_id(), zoom));
+        ctx.set_zoom_factor(zoom * fit);
+        return false;
+    }
+
+    ctx.data_mut(|data| data.remove::<f32>(restore_zoom_id()));
+    ctx.send_viewport_cmd(ViewportCommand::Screenshot(UserData::new(ScreenshotCrop {
+        crop: crop.intersect(window),
+        pixels_per_point: ctx.pixels_per_point(),
+        restore_zoom,
+    })));
+    true
+}
+
+/// Drops a capture that had to zoom out first, puts the zoom back
+pub fn cancel_screenshot(ctx: &egui::Context) {
+    if let Some(zoom) = ctx.data_mut(|data| data.remove_temp::<f32>(restore_zoom_id())) {
+        ctx.set_zoom_factor(zoom);
+    }
+}
+
+/// Screenshot that arrived this frame, if any
+pub fn take_screenshot(ctx: &egui::Context) -> Option<ColorImage> {
+    let (image, crop, pixels_per_point, restore_zoom) = ctx.input(|i| {
+        i.events.iter().find_map(|event| match event {
+            egui::Event::Screenshot {
+                user_data, image, ..
+            } => user_data
+                .data
+                .as_ref()
+                .and_then(|data| data.downcast_ref::<ScreenshotCrop>())
+                .map(|crop| {
+                    (
+                        Arc::clone(image),
+                        crop.crop,
+                        crop.pixels_per_point,
+                        crop.restore_zoom,
+                    )
+                }),
+            _ => None,
+        })
+    })?;
+
+    if let Some(zoom) = restore_zoom {
+        ctx.set_zoom_factor(zoom);
+    }
+
+    Some(image.region(&crop, Some(pixels_per_point)))
+}
+
+pub fn encode_png(image: &ColorImage) -> Result<Vec<u8>, png::EncodingError> {
+    let mut bytes = Vec::new();
+
+    let mut encoder = png::Encoder::new(&mut bytes, image.width() as u32, image.height() as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(image.as_raw())?;
+    writer.finish()?;
+
+    Ok(bytes)
+}
@@ -0,0 +1,185 @@
+use super::View;
+use crate::{
+    CrynContext,
+    models::{OrderedWeekday, RecordStatus, TimeFormat, format_date_time},
+    services::{MeetingTime, RecordChange, RecordDiff},
+    windows::{Window, main_window::CONTENT_PADDING},
+};
+use egui::{Color32, Grid, RichText, ScrollArea, epaint::MarginF32};
+
+const ADDED_COLOR: Color32 = Color32::from_rgb(60, 160, 90);
+
+/// What changed since the previous import, ie seats filling up or groups closing
+pub struct ChangesView {
+    only_selected: bool,
+}
+
+impl ChangesView {
+    pub fn new() -> Self {
+        Self {
+            only_selected: true,
+        }
+    }
+
+    fn render_diffs(ui: &mut egui::Ui, diffs: &[&RecordDiff], time_format: TimeFormat) {
+        let mut last_code = None;
+        for diff in diffs {
+            // Diffs come sorted by code, one heading per course
+            if last_code != Some(&diff.code) {
+                if last_code.is_some() {
+                    ui.add_space(12.0);
+                }
+
+                ui.label(RichText::new(format!("{} {}", diff.code, diff.name)).strong());
+                last_code = Some(&diff.code);
+            }
+
+            Grid::new(format!(
+                "changes_{}_{:?}_{}_{:?}",
+                diff.code, diff.record_type, diff.group, diff.meeting
+            ))
+            .num_columns(2)
+            .min_col_width(220.0)
+            .show(ui, |ui| {
+                ui.weak(format!(
+                    "{:?} G{} · {}",
+                    diff.record_type,
+                    diff.group,
+                    format_meeting(&diff.meeting, time_format)
+                ));
+
+                ui.horizontal_wrapped(|ui| {
+                    for change in &diff.changes {
+                        let (text, color) = describe_change(ui, change, time_format);
+                        match color {
+                            Some(color) => ui.colored_label(color, text),
+                            None => ui.label(text),
+                        };
+                    }
+                });
+                ui.end_row();
+            });
+        }
+    }
+}
+
+impl Default for ChangesView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn format_meeting(meeting: &MeetingTime, time_format: TimeFormat) -> String {
+    format!(
+        "{} {}–{}",
+        OrderedWeekday::from(meeting.day),
+        time_format.format(meeting.start_time),
+        time_format.format(meeting.end_time)
+    )
+}
+
+fn describe_change(
+    ui: &egui::Ui,
+    change: &RecordChange,
+    time_format: TimeFormat,
+) -> (String, Option<Color32>) {
+    let error_color = ui.visuals().error_fg_color;
+
+    match change {
+        RecordChange::Added => ("New group".to_owned(), Some(ADDED_COLOR)),
+        RecordChange::Removed => ("Removed".to_owned(), Some(error_color)),
+        RecordChange::Rescheduled { old, .. } => (
+            format!("Moved from {}", format_meeting(old, time_format)),
+            None,
+        ),
+        RecordChange::Relocated { old, new } => (format!("{} → {}", old, new), None),
+        RecordChange::StatusChanged { old, new } => {
+            let color = match new {
+                RecordStatus::Closed => Some(error_color),
+                RecordStatus::Open => Some(ADDED_COLOR),
+                _ => None,
+            };
+            (format!("{} → {}", old, new), color)
+        }
+        RecordChange::SeatsChanged { enrolled, waiting } => {
+            let mut parts = Vec::new();
+            if *enrolled != 0 {
+                parts.push(format!("{:+} enrolled", enrolled));
+            }
+            if *waiting != 0 {
+                parts.push(format!("{:+} waiting", waiting));
+            }
+            (parts.join(", "), None)
+        }
+    }
+}
+
+impl View for ChangesView {
+    fn name(&self) -> &str {
+        "Changes"
+    }
+
+    fn padding(&self) -> Option<MarginF32> {
+        Some(MarginF32::same(CONTENT_PADDING * 2.0))
+    }
+
+    fn on_show(&mut self, _app_ctx: &CrynContext) {}
+
+    fn on_hide(&mut self, _app_ctx: &CrynContext) {}
+
+    fn on_gui(&mut self, ui: &mut egui::Ui, app_ctx: &CrynContext, _window: &mut dyn Window) {
+        let snapshot_diff = app_ctx.snapshot_diff.borrow();
+        let Some(snapshot_diff) = snapshot_diff.as_ref() else {
+            ui.centered_and_justified(|ui| {
+                ui.heading("Import newer course data to see what changed");
+            });
+            return;
+        };
+
+        let time_format = app_ctx.settings.borrow().time_format;
+
+        ui.heading("Changes");
+        match (
+            snapshot_diff.old_snapshot_time,
+            snapshot_diff.new_snapshot_time,
+        ) {
+            (Some(old), Some(new)) => ui.weak(format!(
+                "Between {} and {}",
+                format_date_time(old, time_format),
+                format_date_time(new, time_format)
+            )),
+            _ => ui.weak("Since the previous import"),
+        };
+
+        ui.add_space(8.0);
+        ui.checkbox(&mut self.only_selected, "Only selected courses");
+        ui.separator();
+
+        let diffs: Vec<&RecordDiff> = if self.only_selected {
+            let selected_codes: Vec<String> = app_ctx
+                .course_manager
+                .borrow()
+                .course_definitions
+                .iter()
+                .map(|def| def.borrow())
+                .filter(|def| def.selected)
+                .map(|def| def.code.clone())
+                .collect();
+
+            snapshot_diff.for_courses(&selected_codes)
+        } else {
+            snapshot_diff.records.iter().collect()
+        };
+
+        if diffs.is_empty() {
+            ui.centered_and_justified(|ui| {
+                ui.heading("Nothing changed");
+            });
+            return;
+        }
+
+        ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| Self::render_diffs(ui, &diffs, time_format));
+    }
+}
@@ -1,19 +1,30 @@
 use super::View;
 use crate::{
     CrynContext,
-    models::CourseDefinition,
+    models::{Availability, CourseDefinition, CourseRecordType, GroupFilter, format_date_time},
+    services::{
+        CourseManager, catalogue_to_csv, catalogue_to_json, find_conflicts,
+        group_conflicts_by_course,
+    },
+    utils,
+    views::{availability_color, timetable_view::grid::CONFLICT_COLOR},
     windows::{Window, main_window::CONTENT_PADDING},
 };
 use egui::{
-    Align, CentralPanel, Frame, Label, Layout, Response, Sense, TextEdit, TextWrapMode,
+    Align, CentralPanel, ComboBox, Frame, Label, Layout, Response, Sense, TextEdit, TextWrapMode,
     TopBottomPanel, epaint::MarginF32,
 };
 use egui_extras::{Column, TableBuilder};
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    rc::Rc,
+};
 
 const SEARCH_HEIGHT: f32 = 35.0;
 const HEADER_HEIGHT: f32 = 25.0;
 const ROW_HEIGHT: f32 = 30.0;
+const STATUS_HEIGHT: f32 = 24.0;
 
 pub struct CoursesView {
     hovered_row_idx: Option<usize>,
@@ -22,6 +33,19 @@ pub struct CoursesView {
     search_query: String,
     last_search_query: String,
     filtered_indices: Vec<usize>,
+    filter_dirty: bool,
+
+    /// Course codes per building and the one to show, all buildings if None
+    buildings: Vec<(String, BTreeSet<String>)>,
+    building_filter: Option<String>,
+
+    /// Conflicts between the selected courses, keyed by course code
+    /// Kept as text since definitions are mutably borrowed while rendering rows
+    course_conflicts: HashMap<String, Vec<String>>,
+    conflicts_dirty: bool,
+
+    /// Availability of every group, keyed by course code
+    course_availability: HashMap<String, Vec<(CourseRecordType, i32, Availability)>>,
 }
 
 impl CoursesView {
@@ -33,12 +57,68 @@ impl CoursesView {
             search_query: String::new(),
             last_search_query: String::new(),
             filtered_indices: Vec::new(),
+            filter_dirty: true,
+            buildings: Vec::new(),
+            building_filter: None,
+            course_conflicts: HashMap::new(),
+            conflicts_dirty: true,
+            course_availability: HashMap::new(),
         }
     }
 
-    fn update_filter(&mut self, definitions: &Vec<Rc<RefCell<CourseDefinition>>>) {
+    fn update_availability(&mut self, app_ctx: &CrynContext) {
+        let mut groups = BTreeMap::<(String, CourseRecordType, i32), Availability>::new();
+        app_ctx
+            .course_manager
+            .borrow()
+            .course_records
+            .iter()
+            .for_each(|record| {
+                let record = record.borrow();
+                let availability = record.availability();
+                groups
+                    .entry((
+                        record.course_definition.borrow().code.clone(),
+                        record.record_type,
+                        record.group,
+                    ))
+                    .and_modify(|group| *group = group.worst(availability))
+                    .or_insert(availability);
+            });
+
+        self.course_availability.clear();
+        for ((code, record_type, group), availability) in groups {
+            self.course_availability.entry(code).or_default().push((
+                record_type,
+                group,
+                availability,
+            ));
+        }
+    }
+
+    fn update_conflicts(&mut self, app_ctx: &CrynContext) {
+        if !self.conflicts_dirty {
+            return;
+        }
+
+        let available_records = app_ctx
+            .course_manager
+            .borrow()
+            .get_available_course_records();
+        let time_format = app_ctx.settings.borrow().time_format;
+        self.course_conflicts = group_conflicts_by_course(&find_conflicts(&available_records))
+            .into_iter()
+            .map(|(code, conflicts)| {
+                let descriptions = conflicts.iter().map(|c| c.describe(time_format)).collect();
+                (code, descriptions)
+            })
+            .collect();
+        self.conflicts_dirty = false;
+    }
+
+    fn update_filter(&mut self, definitions: &[Rc<RefCell<CourseDefinition>>]) {
         let query = self.search_query.to_lowercase();
-        if query == self.last_search_query {
+        if query == self.last_search_query && !self.filter_dirty {
             return;
         }
 
@@ -48,31 +128,85 @@ impl CoursesView {
 
         if query.is_empty() {
             self.create_default_indices(definitions);
-            self.last_search_query = query;
-            return;
+        } else {
+            self.search_indices(definitions, &query);
+        }
+
+        // Narrow down to the picked building
+        let building_codes = self.building_filter.as_ref().and_then(|filter| {
+            self.buildings
+                .iter()
+                .find(|(building, _)| building == filter)
+                .map(|(_, codes)| codes)
+        });
+        if let Some(codes) = building_codes {
+            self.filtered_indices
+                .retain(|&index| codes.contains(&definitions[index].borrow().code));
         }
 
+        self.last_search_query = query;
+        self.filter_dirty = false;
+    }
+
+    fn search_indices(&mut self, definitions: &[Rc<RefCell<CourseDefinition>>], query: &str) {
         let mut code_indices: Vec<usize> = Vec::new();
         let mut name_indices: Vec<usize> = Vec::new();
 
         definitions.iter().enumerate().for_each(|(index, def)| {
             let def = def.borrow();
-            if def.code.to_lowercase().contains(&query) {
+            if def.code.to_lowercase().contains(query) {
                 code_indices.push(index);
             }
 
-            if def.name.to_lowercase().contains(&query) {
+            if def.name.to_lowercase().contains(query) {
                 name_indices.push(index);
             }
         });
 
         self.filtered_indices = code_indices;
         self.filtered_indices.extend(name_indices);
+    }
 
-        self.last_search_query = query;
+    fn update_buildings(&mut self, app_ctx: &CrynContext) {
+        self.buildings = app_ctx.course_manager.borrow().courses_by_building();
+
+        // Building might be gone after a reload
+        if let Some(filter) = &self.building_filter
+            && !self
+                .buildings
+                .iter()
+                .any(|(building, _)| building == filter)
+        {
+            self.building_filter = None;
+        }
+    }
+
+    fn render_building_filter(&mut self, ui: &mut egui::Ui) {
+        let selected_text = match &self.building_filter {
+            Some(building) => format!("Building {}", building),
+            None => "All buildings".to_owned(),
+        };
+
+        let previous = self.building_filter.clone();
+        ComboBox::from_id_salt("courses_view_building_filter")
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.building_filter, None, "All buildings");
+                for (building, codes) in &self.buildings {
+                    ui.selectable_value(
+                        &mut self.building_filter,
+                        Some(building.clone()),
+                        format!("Building {} ({})", building, codes.len()),
+                    );
+                }
+            });
+
+        if self.building_filter != previous {
+            self.filter_dirty = true;
+        }
     }
 
-    fn create_default_indices(&mut self, definitions: &Vec<Rc<RefCell<CourseDefinition>>>) {
+    fn create_default_indices(&mut self, definitions: &[Rc<RefCell<CourseDefinition>>]) {
         // Sort by selection, then by code
         let mut indices: Vec<usize> = (0..definitions.len()).collect();
         indices.sort_by(|&a, &b| {
@@ -116,16 +250,104 @@ impl CoursesView {
     }
 }
 
+/// Saves the loaded courses in our own formats
+fn render_export_menu(ui: &mut egui::Ui, course_manager: &CourseManager) {
+    let exported = if ui.button("JSON catalogue").clicked() {
+        Some(
+            catalogue_to_json(course_manager)
+                .map_err(|err| err.to_string())
+                .and_then(|json| {
+                    utils::save_file("courses.json", "JSON", &["json"], json.as_bytes())
+                }),
+        )
+    } else if ui.button("CSV catalogue").clicked() {
+        Some(
+            catalogue_to_csv(course_manager)
+                .map_err(|err| err.to_string())
+                .and_then(|csv| utils::save_file("courses.csv", "CSV", &["csv"], csv.as_bytes())),
+        )
+    } else {
+        None
+    };
+
+    if let Some(exported) = exported {
+        if let Err(err) = exported {
+            utils::log(&err);
+        }
+
+        ui.close();
+    }
+}
+
+/// Any/pinned group per record type
+fn render_pin_menu(
+    ui: &mut egui::Ui,
+    groups: &[(CourseRecordType, i32, Availability)],
+    pinned_groups: &mut BTreeMap<CourseRecordType, i32>,
+) {
+    let summary = if pinned_groups.is_empty() {
+        "Any".to_owned()
+    } else {
+        pinned_groups
+            .iter()
+            .map(|(record_type, group)| format!("{:?} G{}", record_type, group))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    ui.menu_button(summary, |ui| {
+        let mut first = true;
+        for record_type in [CourseRecordType::Lecture, CourseRecordType::Tutorial] {
+            let type_groups: Vec<&(CourseRecordType, i32, Availability)> = groups
+                .iter()
+                .filter(|(group_type, _, _)| *group_type == record_type)
+                .collect();
+            if type_groups.is_empty() {
+                continue;
+            }
+
+            if !std::mem::take(&mut first) {
+                ui.separator();
+            }
+            ui.strong(format!("{:?}", record_type));
+
+            let mut pinned = pinned_groups.get(&record_type).copied();
+            ui.selectable_value(&mut pinned, None, "Any group");
+            for (_, group, availability) in type_groups {
+                ui.selectable_value(
+                    &mut pinned,
+                    Some(*group),
+                    format!("G{} · {}", group, availability),
+                );
+            }
+
+            match pinned {
+                Some(group) => pinned_groups.insert(record_type, group),
+                None => pinned_groups.remove(&record_type),
+            };
+        }
+    });
+}
+
+impl Default for CoursesView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl View for CoursesView {
     fn name(&self) -> &str {
         "Courses"
     }
 
     fn on_show(&mut self, app_ctx: &CrynContext) {
-        if self.search_query.is_empty() {
-            let definitions = &app_ctx.course_manager.borrow().course_definitions;
-            self.create_default_indices(definitions);
-        }
+        self.conflicts_dirty = true;
+        self.update_conflicts(app_ctx);
+        self.update_availability(app_ctx);
+        self.update_buildings(app_ctx);
+
+        // Force a re-filter, data might have been reloaded
+        self.filter_dirty = true;
     }
 
     fn on_hide(&mut self, _app_ctx: &CrynContext) {
@@ -152,18 +374,65 @@ impl View for CoursesView {
             )
             .exact_height(SEARCH_HEIGHT)
             .show_inside(ui, |ui| {
-                ui.add_sized(
-                    ui.available_size(),
-                    TextEdit::singleline(&mut self.search_query)
-                        .hint_text("Search by code or name...")
-                        .frame(false)
-                        .vertical_align(Align::Center)
-                        .horizontal_align(Align::Center),
-                );
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if !self.buildings.is_empty() {
+                        self.render_building_filter(ui);
+                    }
+
+                    ui.add_sized(
+                        ui.available_size(),
+                        TextEdit::singleline(&mut self.search_query)
+                            .hint_text("Search by code or name...")
+                            .frame(false)
+                            .vertical_align(Align::Center)
+                            .horizontal_align(Align::Center),
+                    );
+                });
+            });
+
+        // Import summary
+        TopBottomPanel::bottom("courses_view_bottom_panel")
+            .frame(
+                Frame::new()
+                    .inner_margin(MarginF32::symmetric(CONTENT_PADDING, 0.0))
+                    .fill(ui.visuals().faint_bg_color),
+            )
+            .exact_height(STATUS_HEIGHT)
+            .show_inside(ui, |ui| {
+                let course_manager = app_ctx.course_manager.borrow();
+                let report = &course_manager.parse_report;
+
+                ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
+                    let response = ui.weak(report.to_string());
+                    if !report.rejected.is_empty() {
+                        response.on_hover_ui(|ui| {
+                            report.rejected.iter().for_each(|row| {
+                                ui.label(row.to_string());
+                            });
+                        });
+                    }
+
+                    // Catalogue export and snapshot time on the far right
+                    ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                        ui.menu_button("Export", |ui| {
+                            render_export_menu(ui, &course_manager);
+                        });
+
+                        if let Some(snapshot_time) = course_manager.snapshot_time() {
+                            let time_format = app_ctx.settings.borrow().time_format;
+                            ui.weak(format!(
+                                "Snapshot {}",
+                                format_date_time(snapshot_time, time_format)
+                            ))
+                            .on_hover_text("When the portal exported this data");
+                        }
+                    });
+                });
             });
 
         // Handle filtering
         self.update_filter(definitions);
+        self.update_conflicts(app_ctx);
 
         if self.filtered_indices.is_empty() {
             ui.centered_and_justified(|ui| {
@@ -192,6 +461,9 @@ impl View for CoursesView {
                     hovered_row_idx,
                     selected_row_idx,
                     any_hovered,
+                    course_conflicts,
+                    conflicts_dirty,
+                    course_availability,
                     ..
                 } = self;
 
@@ -208,7 +480,10 @@ impl View for CoursesView {
                     .column(Column::remainder().clip(true)) // Name
                     .column(Column::initial(80.0)) // Lecs
                     .column(Column::initial(80.0)) // Tuts
+                    .column(Column::initial(90.0)) // Seats
+                    .column(Column::initial(90.0)) // Pins
                     .column(Column::auto().at_least(200.0)) // Flags
+                    .column(Column::initial(110.0)) // Conflicts
                     .header(HEADER_HEIGHT, |mut header| {
                         header.col(|ui| {
                             ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
@@ -234,11 +509,29 @@ impl View for CoursesView {
                             });
                         });
 
+                        header.col(|ui| {
+                            ui.centered_and_justified(|ui| {
+                                ui.strong("Seats");
+                            });
+                        });
+
+                        header.col(|ui| {
+                            ui.centered_and_justified(|ui| {
+                                ui.strong("Pinned");
+                            });
+                        });
+
                         header.col(|ui| {
                             ui.centered_and_justified(|ui| {
                                 ui.strong("Flags");
                             });
                         });
+
+                        header.col(|ui| {
+                            ui.centered_and_justified(|ui| {
+                                ui.strong("Conflicts");
+                            });
+                        });
                     })
                     .body(|body| {
                         body.rows(ROW_HEIGHT, filtered_indices.len(), |mut row| {
@@ -247,9 +540,12 @@ impl View for CoursesView {
                                 name,
                                 flags,
                                 selected,
+                                pinned_groups,
                                 lecture_count,
                                 tutorial_count,
                             } = &mut *definitions[filtered_indices[row.index()]].borrow_mut();
+                            let was_selected = *selected;
+                            let was_pinned = pinned_groups.clone();
 
                             row.set_hovered(*hovered_row_idx == Some(row.index()));
                             row.set_selected(*selected_row_idx == Some(row.index()));
@@ -292,6 +588,56 @@ impl View for CoursesView {
                                 });
                             });
 
+                            // Groups we can still register in
+                            row.col(|ui| {
+                                ui.centered_and_justified(|ui| {
+                                    let Some(groups) = course_availability.get(code) else {
+                                        return;
+                                    };
+
+                                    let strict = GroupFilter {
+                                        skip_closed: true,
+                                        skip_full: true,
+                                    };
+                                    let open_count = groups
+                                        .iter()
+                                        .filter(|(_, _, availability)| strict.allows(*availability))
+                                        .count();
+                                    let text = format!("{}/{} open", open_count, groups.len());
+
+                                    let response = if open_count == 0 {
+                                        ui.colored_label(CONFLICT_COLOR, text)
+                                    } else {
+                                        ui.label(text)
+                                    };
+                                    response.on_hover_ui(|ui| {
+                                        groups.iter().for_each(
+                                            |(record_type, group, availability)| {
+                                                let text = format!(
+                                                    "{:?} G{}: {}",
+                                                    record_type, group, availability
+                                                );
+                                                match availability_color(*availability) {
+                                                    Some(color) => ui.colored_label(color, text),
+                                                    None => ui.label(text),
+                                                };
+                                            },
+                                        );
+                                    });
+                                });
+                            });
+
+                            // Locked groups, the generator varies everything else
+                            row.col(|ui| {
+                                ui.centered_and_justified(|ui| {
+                                    let Some(groups) = course_availability.get(code) else {
+                                        return;
+                                    };
+
+                                    render_pin_menu(ui, groups, pinned_groups);
+                                });
+                            });
+
                             // Flags
                             row.col(|ui| {
                                 ui.centered_and_justified(|ui| {
@@ -304,6 +650,27 @@ impl View for CoursesView {
                                 });
                             });
 
+                            // Conflicts with other selected courses
+                            row.col(|ui| {
+                                ui.centered_and_justified(|ui| {
+                                    let Some(conflicts) =
+                                        course_conflicts.get(code).filter(|_| *selected)
+                                    else {
+                                        return;
+                                    };
+
+                                    ui.colored_label(
+                                        CONFLICT_COLOR,
+                                        format!("{} clashes", conflicts.len()),
+                                    )
+                                    .on_hover_ui(|ui| {
+                                        conflicts.iter().for_each(|conflict| {
+                                            ui.label(conflict);
+                                        });
+                                    });
+                                });
+                            });
+
                             // Handle row events
                             CoursesView::handle_row_events(
                                 &row.response(),
@@ -313,6 +680,11 @@ impl View for CoursesView {
                                 selected_row_idx,
                                 any_hovered,
                             );
+
+                            // Selection or pins changed, conflicts need a refresh
+                            if *selected != was_selected || *pinned_groups != was_pinned {
+                                *conflicts_dirty = true;
+                            }
                         });
                     });
             });
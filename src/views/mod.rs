@@ -1,13 +1,15 @@
+mod changes_view;
 mod courses_view;
-mod placeholder_view;
+mod settings_view;
 mod timetable_view;
 
+pub use changes_view::*;
 pub use courses_view::*;
-pub use placeholder_view::*;
+pub use settings_view::*;
 pub use timetable_view::*;
 
-use crate::{CrynContext, windows::Window};
-use egui::epaint::MarginF32;
+use crate::{CrynContext, models::Availability, windows::Window};
+use egui::{Color32, epaint::MarginF32};
 
 pub trait View {
     /// View name
@@ -32,3 +34,13 @@ pub trait View {
     /// Called every frame when the view is active
     fn on_gui(&mut self, ui: &mut egui::Ui, app_ctx: &CrynContext, window: &mut dyn Window);
 }
+
+/// Green to red, None when we cant tell
+pub fn availability_color(availability: Availability) -> Option<Color32> {
+    match availability {
+        Availability::Open { .. } => Some(Color32::from_rgb(60, 160, 90)),
+        Availability::Waitlisted { .. } => Some(Color32::from_rgb(230, 160, 40)),
+        Availability::Closed => Some(Color32::from_rgb(232, 17, 35)),
+        Availability::UnknownCapacity => None,
+    }
+}
@@ -0,0 +1,267 @@
+use super::View;
+use crate::{
+    CrynContext,
+    models::{CampusMap, OrderedWeekday, Route, Settings, StartupView, Theme, TimeFormat},
+    windows::{Window, main_window::CONTENT_PADDING},
+};
+use chrono::Weekday;
+use egui::{ComboBox, DragValue, Grid, Slider, TextEdit, epaint::MarginF32};
+
+const LABEL_WIDTH: f32 = 180.0;
+const COMBO_WIDTH: f32 = 160.0;
+const PLACE_WIDTH: f32 = 160.0;
+
+pub struct SettingsView {
+    /// Last re-parse failure, the old data stays loaded
+    reparse_error: Option<String>,
+}
+
+impl SettingsView {
+    pub fn new() -> Self {
+        Self {
+            reparse_error: None,
+        }
+    }
+
+    /// Returns whether anything changed and whether courses need a re-parse
+    fn render_settings(ui: &mut egui::Ui, settings: &mut Settings) -> (bool, bool) {
+        let mut changed = false;
+        let mut needs_reparse = false;
+
+        Grid::new("settings_grid")
+            .num_columns(2)
+            .min_col_width(LABEL_WIDTH)
+            .spacing([16.0, 12.0])
+            .show(ui, |ui| {
+                // Theme
+                ui.label("Theme");
+                ComboBox::from_id_salt("settings_theme")
+                    .width(COMBO_WIDTH)
+                    .selected_text(format!("{:?}", settings.theme))
+                    .show_ui(ui, |ui| {
+                        for theme in [Theme::System, Theme::Light, Theme::Dark] {
+                            changed |= ui
+                                .selectable_value(&mut settings.theme, theme, format!("{:?}", theme))
+                                .changed();
+                        }
+                    });
+                ui.end_row();
+
+                // Week start
+                ui.label("First day of the week");
+                ComboBox::from_id_salt("settings_week_start")
+                    .width(COMBO_WIDTH)
+                    .selected_text(OrderedWeekday::from(settings.week_start).to_string())
+                    .show_ui(ui, |ui| {
+                        for day in [Weekday::Sat, Weekday::Sun, Weekday::Mon] {
+                            changed |= ui
+                                .selectable_value(
+                                    &mut settings.week_start,
+                                    day,
+                                    OrderedWeekday::from(day).to_string(),
+                                )
+                                .changed();
+                        }
+                    });
+                ui.end_row();
+
+                // Time format
+                ui.label("Time format");
+                ComboBox::from_id_salt("settings_time_format")
+                    .width(COMBO_WIDTH)
+                    .selected_text(time_format_name(settings.time_format))
+                    .show_ui(ui, |ui| {
+                        for format in [TimeFormat::TwentyFourHour, TimeFormat::TwelveHour] {
+                            changed |= ui
+                                .selectable_value(
+                                    &mut settings.time_format,
+                                    format,
+                                    time_format_name(format),
+                                )
+                                .changed();
+                        }
+                    });
+                ui.end_row();
+
+                // Startup view
+                ui.label("Open on startup");
+                ComboBox::from_id_salt("settings_startup_view")
+                    .width(COMBO_WIDTH)
+                    .selected_text(startup_view_name(settings.startup_view))
+                    .show_ui(ui, |ui| {
+                        for view in [
+                            StartupView::LastUsed,
+                            StartupView::TimeTable,
+                            StartupView::Courses,
+                        ] {
+                            changed |= ui
+                                .selectable_value(
+                                    &mut settings.startup_view,
+                                    view,
+                                    startup_view_name(view),
+                                )
+                                .changed();
+                        }
+                    });
+                ui.end_row();
+
+                // Afternoon threshold, only matters for the portal's 12h times
+                ui.label("Afternoon times start before")
+                    .on_hover_text("Portal times earlier than this hour are moved 12 hours later, ie 1:00 becomes 13:00");
+                let response = ui.add(
+                    Slider::new(&mut settings.afternoon_threshold_hour, 0..=12).suffix(":00"),
+                );
+
+                // Reparsing every drag frame is way too slow, wait for the release
+                needs_reparse =
+                    response.drag_stopped() || (response.changed() && !response.dragged());
+                changed |= response.changed();
+                ui.end_row();
+            });
+
+        (changed, needs_reparse)
+    }
+
+    /// Travel times, returns true if anything changed
+    fn render_campus_map(ui: &mut egui::Ui, campus_map: &mut CampusMap) -> bool {
+        let mut changed = false;
+
+        ui.strong("Walking times");
+        ui.weak("Breaks shorter than these are flagged in the time table");
+        ui.add_space(4.0);
+
+        Grid::new("settings_campus_map_grid")
+            .num_columns(2)
+            .min_col_width(LABEL_WIDTH)
+            .spacing([16.0, 12.0])
+            .show(ui, |ui| {
+                ui.label("Between campuses");
+                changed |= ui
+                    .add(DragValue::new(&mut campus_map.campus_minutes).suffix(" min"))
+                    .changed();
+                ui.end_row();
+
+                ui.label("Between buildings")
+                    .on_hover_text("Buildings of the same campus, 0 never warns");
+                changed |= ui
+                    .add(DragValue::new(&mut campus_map.building_minutes).suffix(" min"))
+                    .changed();
+                ui.end_row();
+            });
+
+        // Campus names or building numbers, overriding the times above
+        ui.add_space(8.0);
+        let mut removed_idx = None;
+        Grid::new("settings_routes_grid")
+            .num_columns(4)
+            .spacing([8.0, 8.0])
+            .show(ui, |ui| {
+                for (route_idx, route) in campus_map.routes.iter_mut().enumerate() {
+                    for place in &mut route.between {
+                        changed |= ui
+                            .add(
+                                TextEdit::singleline(place)
+                                    .hint_text("Campus or building")
+                                    .desired_width(PLACE_WIDTH),
+                            )
+                            .changed();
+                    }
+
+                    changed |= ui
+                        .add(DragValue::new(&mut route.minutes).suffix(" min"))
+                        .changed();
+
+                    if ui.button("Remove").clicked() {
+                        removed_idx = Some(route_idx);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        if let Some(route_idx) = removed_idx {
+            campus_map.routes.remove(route_idx);
+            changed = true;
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("Add route").clicked() {
+                campus_map.routes.push(Route {
+                    between: Default::default(),
+                    minutes: campus_map.campus_minutes,
+                });
+                changed = true;
+            }
+
+            if ui.button("Reset to defaults").clicked() {
+                *campus_map = CampusMap::bundled();
+                changed = true;
+            }
+        });
+
+        changed
+    }
+}
+
+impl Default for SettingsView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn time_format_name(format: TimeFormat) -> &'static str {
+    match format {
+        TimeFormat::TwentyFourHour => "24 hour (13:30)",
+        TimeFormat::TwelveHour => "12 hour (1:30 PM)",
+    }
+}
+
+fn startup_view_name(view: StartupView) -> &'static str {
+    match view {
+        StartupView::LastUsed => "Last used",
+        StartupView::TimeTable => "Time Table",
+        StartupView::Courses => "Courses",
+    }
+}
+
+impl View for SettingsView {
+    fn name(&self) -> &str {
+        "Settings"
+    }
+
+    fn padding(&self) -> Option<MarginF32> {
+        Some(MarginF32::same(CONTENT_PADDING * 2.0))
+    }
+
+    fn on_show(&mut self, _app_ctx: &CrynContext) {}
+
+    fn on_hide(&mut self, _app_ctx: &CrynContext) {}
+
+    fn on_gui(&mut self, ui: &mut egui::Ui, app_ctx: &CrynContext, _window: &mut dyn Window) {
+        ui.heading("Settings");
+        ui.add_space(12.0);
+
+        let mut settings = app_ctx.settings.borrow().clone();
+        let (mut changed, needs_reparse) = Self::render_settings(ui, &mut settings);
+        if !changed && let Some(reparse_error) = &self.reparse_error {
+            ui.add_space(12.0);
+            ui.colored_label(ui.visuals().error_fg_color, reparse_error);
+        }
+
+        ui.add_space(24.0);
+        changed |= Self::render_campus_map(ui, &mut settings.campus_map);
+        if !changed {
+            return;
+        }
+
+        // Apply live
+        settings.apply(ui.ctx());
+        *app_ctx.settings.borrow_mut() = settings;
+
+        if needs_reparse {
+            self.reparse_error = app_ctx
+                .reparse_courses()
+                .err()
+                .map(|err| format!("Couldn't re-read the loaded courses: {}", err));
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use super::grid::{BLOCK_MARGIN, BLOCK_TEXT_PADDING, TimeAxis, time_from_minutes};
+use crate::{
+    models::{BlockedTime, TimeFormat},
+    services::BlockedConflict,
+};
+use chrono::{NaiveTime, Timelike, Weekday};
+use egui::{
+    Align2, Color32, CornerRadius, CursorIcon, FontId, Pos2, Rect, Sense, Stroke, StrokeKind,
+    TextEdit, Vec2,
+};
+
+/// Blocks snap to quarter hours
+const SNAP_MINUTES: u32 = 15;
+
+/// How close to an edge a drag resizes instead of moving
+const EDGE_GRAB_WIDTH: f32 = 6.0;
+const HATCH_SPACING: f32 = 8.0;
+
+const HARD_COLOR: Color32 = Color32::from_rgb(190, 90, 90);
+const SOFT_COLOR: Color32 = Color32::from_rgb(130, 130, 130);
+
+/// What the current drag on the grid does
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlockedDrag {
+    Create {
+        day: Weekday,
+        anchor_minute: u32,
+    },
+
+    /// `grab_offset` is how many minutes after its start the block was grabbed
+    Move {
+        index: usize,
+        grab_offset: f32,
+    },
+    ResizeStart {
+        index: usize,
+    },
+    ResizeEnd {
+        index: usize,
+    },
+}
+
+/// Busy times drawn under the course blocks
+pub struct BlockedTimesLayer<'a> {
+    pub times: &'a mut Vec<BlockedTime>,
+    pub conflicts: &'a [BlockedConflict],
+
+    /// Drag on empty space to add, drag blocks to move or resize them
+    pub editing: bool,
+    pub drag: &'a mut Option<BlockedDrag>,
+    pub time_format: TimeFormat,
+}
+
+/// Draws and edits the busy times of one day, returns true if any changed
+/// `times_rect` is the row minus the day label
+pub fn render_blocked_times(
+    ui: &mut egui::Ui,
+    painter: &egui::Painter,
+    axis: &TimeAxis,
+    day: Weekday,
+    times_rect: Rect,
+    blocked: &mut BlockedTimesLayer<'_>,
+) -> bool {
+    let mut changed = false;
+    let pointer_minute = ui
+        .ctx()
+        .pointer_latest_pos()
+        .map(|pos| axis.minute_at(pos.x - times_rect.left()));
+
+    // Empty space, registered first so the blocks on top win
+    if blocked.editing {
+        let response = ui.interact(
+            times_rect,
+            ui.id().with(("blocked_row", day)),
+            Sense::drag(),
+        );
+
+        if response.drag_started()
+            && let Some(minute) = pointer_minute
+        {
+            *blocked.drag = Some(BlockedDrag::Create {
+                day,
+                anchor_minute: snap(minute, axis),
+            });
+        }
+
+        if let Some(BlockedDrag::Create {
+            day: drag_day,
+            anchor_minute,
+        }) = *blocked.drag
+            && drag_day == day
+            && let Some(minute) = pointer_minute
+        {
+            let current_minute = snap(minute, axis);
+            let (start_minute, end_minute) = (
+                anchor_minute.min(current_minute),
+                anchor_minute.max(current_minute),
+            );
+
+            if response.drag_stopped() {
+                if end_minute > start_minute {
+                    blocked.times.push(BlockedTime::new(
+                        day,
+                        time_from_minutes(start_minute),
+                        time_from_minutes(end_minute),
+                    ));
+                    changed = true;
+                }
+
+                *blocked.drag = None;
+            } else if end_minute > start_minute {
+                let rect = span_rect(axis, times_rect, start_minute, end_minute);
+                paint_hatch(painter, rect, SOFT_COLOR);
+            }
+        }
+    }
+
+    let mut removed_idx = None;
+    for (index, blocked_time) in blocked
+        .times
+        .iter_mut()
+        .enumerate()
+        .filter(|(_, blocked_time)| blocked_time.day == day)
+    {
+        let rect = span_rect(
+            axis,
+            times_rect,
+            to_minutes(blocked_time.start_time),
+            to_minutes(blocked_time.end_time),
+        );
+
+        paint_hatch(
+            painter,
+            rect,
+            if blocked_time.hard {
+                HARD_COLOR
+            } else {
+                SOFT_COLOR
+            },
+        );
+        painter
+            .with_clip_rect(rect.shrink(BLOCK_TEXT_PADDING * 0.5))
+            .text(
+                rect.left_top() + Vec2::splat(BLOCK_TEXT_PADDING),
+                Align2::LEFT_TOP,
+                &blocked_time.label,
+                FontId::proportional(11.0),
+                ui.visuals().text_color(),
+            );
+
+        let sense = if blocked.editing {
+            Sense::click_and_drag()
+        } else {
+            Sense::click()
+        };
+        let response = ui.interact(rect, ui.id().with(("blocked_time", index)), sense);
+
+        if blocked.editing {
+            if let Some(pos) = response.hover_pos() {
+                ui.ctx().set_cursor_icon(match grab_edge(rect, pos) {
+                    Some(_) => CursorIcon::ResizeHorizontal,
+                    None => CursorIcon::Grab,
+                });
+            }
+
+            if response.drag_started()
+                && let (Some(pos), Some(minute)) = (response.interact_pointer_pos(), pointer_minute)
+            {
+                *blocked.drag = Some(match grab_edge(rect, pos) {
+                    Some(true) => BlockedDrag::ResizeStart { index },
+                    Some(false) => BlockedDrag::ResizeEnd { index },
+                    None => BlockedDrag::Move {
+                        index,
+                        grab_offset: minute - to_minutes(blocked_time.start_time) as f32,
+                    },
+                });
+            }
+
+            if response.dragged()
+                && let (Some(drag), Some(minute)) = (*blocked.drag, pointer_minute)
+            {
+                apply_drag(drag, index, blocked_time, minute, axis);
+            }
+
+            if response.drag_stopped() {
+                *blocked.drag = None;
+                changed = true;
+            }
+        }
+
+        response
+            .on_hover_text(blocked_time.describe(blocked.time_format))
+            .context_menu(|ui| {
+                changed |= ui
+                    .add(TextEdit::singleline(&mut blocked_time.label).desired_width(140.0))
+                    .changed();
+                changed |= ui
+                    .checkbox(&mut blocked_time.hard, "Never schedule over it")
+                    .on_hover_text("Otherwise schedules overlapping it only rank lower")
+                    .changed();
+
+                if ui.button("Remove").clicked() {
+                    removed_idx = Some(index);
+                    ui.close();
+                }
+            });
+    }
+
+    if let Some(index) = removed_idx {
+        blocked.times.remove(index);
+        changed = true;
+    }
+
+    changed
+}
+
+fn apply_drag(
+    drag: BlockedDrag,
+    index: usize,
+    blocked_time: &mut BlockedTime,
+    minute: f32,
+    axis: &TimeAxis,
+) {
+    let start_minute = to_minutes(blocked_time.start_time);
+    let end_minute = to_minutes(blocked_time.end_time);
+
+    match drag {
+        BlockedDrag::Move {
+            index: drag_idx,
+            grab_offset,
+        } if drag_idx == index => {
+            let duration = end_minute - start_minute;
+            let latest_start = axis
+                .end_minute
+                .saturating_sub(duration)
+                .max(axis.start_minute);
+            let new_start = snap(minute - grab_offset, axis).min(latest_start);
+
+            blocked_time.start_time = time_from_minutes(new_start);
+            blocked_time.end_time = time_from_minutes(new_start + duration);
+        }
+        BlockedDrag::ResizeStart { index: drag_idx } if drag_idx == index => {
+            let new_start = snap(minute, axis).min(end_minute - SNAP_MINUTES);
+            blocked_time.start_time = time_from_minutes(new_start);
+        }
+        BlockedDrag::ResizeEnd { index: drag_idx } if drag_idx == index => {
+            let new_end = snap(minute, axis).max(start_minute + SNAP_MINUTES);
+            blocked_time.end_time = time_from_minutes(new_end);
+        }
+        _ => {}
+    }
+}
+
+/// Some(true) near the left edge, Some(false) near the right one
+fn grab_edge(rect: Rect, pos: Pos2) -> Option<bool> {
+    if pos.x - rect.left() < EDGE_GRAB_WIDTH {
+        Some(true)
+    } else if rect.right() - pos.x < EDGE_GRAB_WIDTH {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Nearest quarter hour on the axis
+fn snap(minute: f32, axis: &TimeAxis) -> u32 {
+    let snapped = (minute / SNAP_MINUTES as f32).round().max(0.0) as u32 * SNAP_MINUTES;
+    snapped.clamp(axis.start_minute, axis.end_minute)
+}
+
+fn span_rect(axis: &TimeAxis, times_rect: Rect, start_minute: u32, end_minute: u32) -> Rect {
+    Rect::from_x_y_ranges(
+        times_rect.left() + axis.offset(time_from_minutes(start_minute))
+            ..=times_rect.left() + axis.offset(time_from_minutes(end_minute)),
+        times_rect.y_range(),
+    )
+    .shrink(BLOCK_MARGIN)
+}
+
+fn paint_hatch(painter: &egui::Painter, rect: Rect, color: Color32) {
+    painter.rect_filled(rect, CornerRadius::same(4), color.gamma_multiply(0.15));
+
+    // Diagonal stripes, clipped to the block
+    let hatch_painter = painter.with_clip_rect(rect.intersect(painter.clip_rect()));
+    let stroke = Stroke::new(1.0, color.gamma_multiply(0.6));
+    let mut x = rect.left() - rect.height();
+    while x < rect.right() {
+        hatch_painter.line_segment(
+            [
+                Pos2::new(x, rect.bottom()),
+                Pos2::new(x + rect.height(), rect.top()),
+            ],
+            stroke,
+        );
+        x += HATCH_SPACING;
+    }
+
+    painter.rect_stroke(
+        rect,
+        CornerRadius::same(4),
+        Stroke::new(1.0, color),
+        StrokeKind::Inside,
+    );
+}
+
+fn to_minutes(time: NaiveTime) -> u32 {
+    time.num_seconds_from_midnight() / 60
+}
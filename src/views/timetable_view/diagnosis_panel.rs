@@ -0,0 +1,77 @@
+use super::grid::CONFLICT_COLOR;
+use crate::{
+    models::TimeFormat,
+    services::{RecordConflict, ScheduleDiagnosis},
+};
+use egui::ScrollArea;
+
+/// Why generation came up empty, returns a course the user chose to deselect
+pub fn render_diagnosis_panel(
+    ui: &mut egui::Ui,
+    diagnosis: &ScheduleDiagnosis,
+    clashes: &[RecordConflict],
+    time_format: TimeFormat,
+) -> Option<String> {
+    let mut deselected = None;
+
+    ui.heading("No conflict-free schedule");
+    ui.add_space(4.0);
+
+    ScrollArea::vertical().show(ui, |ui| {
+        // Nothing left to pick from, clashes dont matter then
+        if !diagnosis.empty_slots.is_empty() {
+            ui.strong("No groups left for");
+            for slot in &diagnosis.empty_slots {
+                ui.colored_label(
+                    CONFLICT_COLOR,
+                    format!("{} {:?}", slot.course_code, slot.record_type),
+                );
+
+                for left_out in &slot.left_out {
+                    ui.weak(format!("  G{}: {}", left_out.group, left_out.reason));
+                }
+            }
+
+            ui.separator();
+        }
+
+        ui.strong("These courses cant be taken together");
+        ui.label(diagnosis.conflicting_courses.join(", "));
+
+        if !clashes.is_empty() {
+            ui.collapsing(format!("{} clashes", clashes.len()), |ui| {
+                for clash in clashes {
+                    ui.label(clash.describe(time_format));
+                }
+            });
+        }
+
+        if diagnosis.suggestions.is_empty() {
+            return;
+        }
+
+        ui.separator();
+        ui.strong("Try dropping");
+
+        for suggestion in &diagnosis.suggestions {
+            ui.horizontal(|ui| {
+                let count = if suggestion.capped {
+                    format!("{}+", suggestion.schedule_count)
+                } else {
+                    suggestion.schedule_count.to_string()
+                };
+
+                ui.label(format!(
+                    "{} for {} schedules",
+                    suggestion.course_code, count
+                ));
+
+                if ui.small_button("Deselect").clicked() {
+                    deselected = Some(suggestion.course_code.clone());
+                }
+            });
+        }
+    });
+
+    deselected
+}
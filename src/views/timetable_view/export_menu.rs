@@ -0,0 +1,53 @@
+use crate::{
+    models::{CourseRecord, Semester},
+    services::export_icalendar,
+    utils,
+};
+use egui_extras::DatePickerButton;
+use std::{cell::RefCell, rc::Rc};
+
+/// Calendar export for the shown records, saves right away
+pub fn render_export_menu(
+    ui: &mut egui::Ui,
+    semester: &mut Semester,
+    records: &[Rc<RefCell<CourseRecord>>],
+) {
+    ui.strong("Semester");
+
+    egui::Grid::new("export_semester_grid")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("First day");
+            ui.add(DatePickerButton::new(&mut semester.start).id_salt("export_semester_start"));
+            ui.end_row();
+
+            ui.label("Last day");
+            ui.add(DatePickerButton::new(&mut semester.end).id_salt("export_semester_end"));
+            ui.end_row();
+        });
+
+    if !semester.is_valid() {
+        ui.colored_label(
+            ui.visuals().error_fg_color,
+            "Semester ends before it starts",
+        );
+    }
+
+    ui.separator();
+
+    let can_export = semester.is_valid() && !records.is_empty();
+    if ui
+        .add_enabled(can_export, egui::Button::new("Save calendar (.ics)"))
+        .on_hover_text("Weekly events for every shown session, for Google Calendar, Outlook and co")
+        .clicked()
+    {
+        let calendar = export_icalendar(records, semester, chrono::Utc::now());
+        if let Err(err) =
+            utils::save_file("schedule.ics", "iCalendar", &["ics"], calendar.as_bytes())
+        {
+            utils::log(&err);
+        }
+
+        ui.close();
+    }
+}
@@ -0,0 +1,446 @@
+use super::blocked_times::{BlockedTimesLayer, render_blocked_times};
+use crate::{
+    models::{CourseRecord, CourseSpan, OrderedWeekday, TimeFormat},
+    services::{RecordConflict, TightTransfer},
+    views::availability_color,
+};
+use chrono::{NaiveTime, Timelike, Weekday};
+use egui::{
+    Align2, Color32, CornerRadius, FontId, Pos2, Rect, ScrollArea, Sense, Stroke, StrokeKind, Vec2,
+    ecolor::Hsva,
+};
+use std::{collections::BTreeMap, rc::Rc};
+
+pub const TIMESLOT_WIDTH: f32 = 95.0;
+pub const TIMESLOT_HEIGHT: f32 = 43.0;
+
+pub const DAY_WIDTH: f32 = 158.0;
+pub const DAY_HEIGHT: f32 = 42.0;
+
+pub const BLOCK_MARGIN: f32 = 2.0;
+pub const BLOCK_TEXT_PADDING: f32 = 4.0;
+
+const CONFLICT_STROKE_WIDTH: f32 = 2.5;
+const AVAILABILITY_DOT_RADIUS: f32 = 3.5;
+pub const CONFLICT_COLOR: Color32 = Color32::from_rgb(232, 17, 35);
+
+const TRANSFER_MARK_WIDTH: f32 = 3.0;
+pub const TRANSFER_COLOR: Color32 = Color32::from_rgb(240, 140, 0);
+
+/// Hours shown while editing busy times on an otherwise empty grid
+const EDITING_HOURS: (u32, u32) = (8, 20);
+
+/// Visible time range in minutes since midnight, snapped to whole hours
+pub struct TimeAxis {
+    pub start_minute: u32,
+    pub end_minute: u32,
+}
+
+impl TimeAxis {
+    /// Covers every record and blocked time on the shown days
+    fn new(
+        span_map: &BTreeMap<OrderedWeekday, CourseSpan>,
+        blocked: &BlockedTimesLayer<'_>,
+        days: &[OrderedWeekday],
+    ) -> Option<Self> {
+        let blocked_times = blocked
+            .times
+            .iter()
+            .filter(|blocked_time| days.contains(&blocked_time.day.into()));
+
+        let mut min_from = span_map
+            .values()
+            .filter_map(|span| span.min_from())
+            .chain(
+                blocked_times
+                    .clone()
+                    .map(|blocked_time| blocked_time.start_time),
+            )
+            .min();
+        let mut max_to = span_map
+            .values()
+            .filter_map(|span| span.max_to())
+            .chain(blocked_times.map(|blocked_time| blocked_time.end_time))
+            .max();
+
+        // Room to drag new blocks into
+        if blocked.editing {
+            let (first_hour, last_hour) = EDITING_HOURS;
+            min_from = min_from.min(NaiveTime::from_hms_opt(first_hour, 0, 0));
+            max_to = max_to.max(NaiveTime::from_hms_opt(last_hour, 0, 0));
+        }
+
+        let (min_from, max_to) = (min_from?, max_to?);
+        let start_minute = min_from.hour() * 60;
+        let end_minute = (to_minutes(max_to) as u32).div_ceil(60) * 60;
+
+        Some(Self {
+            start_minute,
+            end_minute: end_minute.max(start_minute + 60),
+        })
+    }
+
+    fn hour_count(&self) -> u32 {
+        (self.end_minute - self.start_minute) / 60
+    }
+
+    /// Horizontal offset of a time from the start of the axis
+    pub fn offset(&self, time: NaiveTime) -> f32 {
+        (to_minutes(time) - self.start_minute as f32) / 60.0 * TIMESLOT_WIDTH
+    }
+
+    /// Minutes since midnight under a horizontal offset, not snapped or clamped
+    pub fn minute_at(&self, offset: f32) -> f32 {
+        self.start_minute as f32 + offset / TIMESLOT_WIDTH * 60.0
+    }
+}
+
+pub struct GridResponse {
+    /// Visible part of the grid, all of it while capturing, None if there was nothing to draw
+    pub rect: Option<Rect>,
+
+    /// A group got pinned or unpinned from a block's context menu
+    pub pins_changed: bool,
+
+    /// Busy times were added, moved, edited or removed
+    pub blocked_times_changed: bool,
+}
+
+pub fn render_grid(
+    ui: &mut egui::Ui,
+    span_map: &BTreeMap<OrderedWeekday, CourseSpan>,
+    conflicts: &[RecordConflict],
+    transfers: &[TightTransfer],
+    blocked: &mut BlockedTimesLayer<'_>,
+    week_start: Weekday,
+    capturing: bool,
+) -> GridResponse {
+    let mut pins_changed = false;
+    let mut blocked_times_changed = false;
+    let time_format = blocked.time_format;
+
+    // Every day while editing so blocks can go on days without classes
+    let mut days: Vec<OrderedWeekday> = if blocked.editing {
+        OrderedWeekday::week(week_start)
+            .map(OrderedWeekday::from)
+            .collect()
+    } else {
+        span_map
+            .keys()
+            .copied()
+            .chain(
+                blocked
+                    .times
+                    .iter()
+                    .map(|blocked_time| blocked_time.day.into()),
+            )
+            .collect()
+    };
+    days.sort_by_key(|day| day.days_since(week_start));
+    days.dedup();
+
+    let Some(axis) = TimeAxis::new(span_map, blocked, &days) else {
+        return GridResponse {
+            rect: None,
+            pins_changed,
+            blocked_times_changed,
+        };
+    };
+
+    // Precompute lanes so we know every row height beforehand
+    let rows: Vec<_> = days
+        .iter()
+        .map(|day| {
+            let (records, lane_count) = span_map
+                .get(day)
+                .map(|span| span.layout_lanes())
+                .unwrap_or_default();
+            (day, records, lane_count.max(1))
+        })
+        .collect();
+
+    let width = DAY_WIDTH + axis.hour_count() as f32 * TIMESLOT_WIDTH;
+    let height = DAY_HEIGHT
+        + rows
+            .iter()
+            .map(|(_, _, lane_count)| *lane_count as f32 * TIMESLOT_HEIGHT)
+            .sum::<f32>();
+
+    let mut paint_grid = |ui: &mut egui::Ui| {
+        let (rect, _) = ui.allocate_exact_size(Vec2::new(width, height), Sense::hover());
+        let painter = ui.painter_at(rect);
+        let visuals = ui.visuals().clone();
+        let grid_stroke = Stroke::new(1.0, visuals.widgets.noninteractive.bg_stroke.color);
+
+        // Time header
+        for hour_idx in 0..=axis.hour_count() {
+            let x = rect.left() + DAY_WIDTH + hour_idx as f32 * TIMESLOT_WIDTH;
+            painter.vline(x, rect.y_range(), grid_stroke);
+
+            if hour_idx < axis.hour_count() {
+                let hour = axis.start_minute / 60 + hour_idx;
+                painter.text(
+                    Pos2::new(x + TIMESLOT_WIDTH * 0.5, rect.top() + DAY_HEIGHT * 0.5),
+                    Align2::CENTER_CENTER,
+                    time_format.format_hour(hour),
+                    FontId::proportional(13.0),
+                    visuals.text_color(),
+                );
+            }
+        }
+
+        painter.hline(rect.x_range(), rect.top() + DAY_HEIGHT, grid_stroke);
+
+        // Day rows
+        let mut row_top = rect.top() + DAY_HEIGHT;
+        for (day, records, lane_count) in &rows {
+            let row_height = *lane_count as f32 * TIMESLOT_HEIGHT;
+            let row_rect = Rect::from_min_size(
+                Pos2::new(rect.left(), row_top),
+                Vec2::new(width, row_height),
+            );
+
+            painter.text(
+                Pos2::new(row_rect.left() + DAY_WIDTH * 0.5, row_rect.center().y),
+                Align2::CENTER_CENTER,
+                day.to_string(),
+                FontId::proportional(14.0),
+                visuals.strong_text_color(),
+            );
+
+            // Busy times go under the course blocks
+            let times_rect = Rect::from_min_max(
+                Pos2::new(row_rect.left() + DAY_WIDTH, row_rect.top()),
+                row_rect.max,
+            );
+            blocked_times_changed |=
+                render_blocked_times(ui, &painter, &axis, day.weekday(), times_rect, blocked);
+
+            // Course blocks, stacked by lane
+            for (record_idx, (record_rc, lane)) in records.iter().enumerate() {
+                let record = record_rc.borrow();
+                let block_rect = Rect::from_min_max(
+                    Pos2::new(
+                        row_rect.left() + DAY_WIDTH + axis.offset(record.start_time),
+                        row_top + *lane as f32 * TIMESLOT_HEIGHT,
+                    ),
+                    Pos2::new(
+                        row_rect.left() + DAY_WIDTH + axis.offset(record.end_time),
+                        row_top + (*lane + 1) as f32 * TIMESLOT_HEIGHT,
+                    ),
+                )
+                .shrink(BLOCK_MARGIN);
+
+                let record_conflicts: Vec<&RecordConflict> = conflicts
+                    .iter()
+                    .filter(|conflict| conflict.involves(record_rc))
+                    .collect();
+
+                let record_transfers: Vec<&TightTransfer> = transfers
+                    .iter()
+                    .filter(|transfer| transfer.involves(record_rc))
+                    .collect();
+
+                let blocked_conflicts: Vec<String> = blocked
+                    .conflicts
+                    .iter()
+                    .filter(|conflict| Rc::ptr_eq(&conflict.record, record_rc))
+                    .map(|conflict| conflict.blocked_time.describe(time_format))
+                    .collect();
+                let is_blocked = blocked.conflicts.iter().any(|conflict| {
+                    conflict.blocked_time.hard && Rc::ptr_eq(&conflict.record, record_rc)
+                });
+
+                paint_block(
+                    &painter,
+                    block_rect,
+                    &record,
+                    !record_conflicts.is_empty() || is_blocked,
+                );
+
+                // Mark the session we might walk in late to
+                if record_transfers
+                    .iter()
+                    .any(|transfer| Rc::ptr_eq(&transfer.to, record_rc))
+                {
+                    let mark_rect = Rect::from_min_max(
+                        block_rect.left_top(),
+                        block_rect.left_bottom() + Vec2::new(TRANSFER_MARK_WIDTH, 0.0),
+                    );
+                    painter.rect_filled(mark_rect, CornerRadius::same(2), TRANSFER_COLOR);
+                }
+
+                let mut description = describe_record(&record, time_format);
+                record_conflicts.iter().for_each(|conflict| {
+                    description.push_str(&format!("\n\n{}", conflict.describe(time_format)));
+                });
+                record_transfers.iter().for_each(|transfer| {
+                    description.push_str(&format!(
+                        "\n\nRushed walk: {}",
+                        transfer.describe(time_format)
+                    ));
+                });
+                blocked_conflicts.iter().for_each(|blocked_time| {
+                    description.push_str(&format!("\n\nDuring {}", blocked_time));
+                });
+
+                ui.interact(
+                    block_rect,
+                    ui.id()
+                        .with(("timetable_block", day.to_string(), record_idx)),
+                    Sense::click(),
+                )
+                .on_hover_text(description)
+                .context_menu(|ui| {
+                    pins_changed |= render_pin_menu(ui, &record);
+                });
+            }
+
+            row_top += row_height;
+            painter.hline(rect.x_range(), row_top, grid_stroke);
+        }
+
+        rect
+    };
+
+    let rect = if capturing {
+        // Whole grid from its top left corner, drawn over whatever is next to it for this frame
+        ui.scope(|ui| {
+            ui.set_clip_rect(ui.ctx().content_rect());
+            paint_grid(ui)
+        })
+        .inner
+    } else {
+        // Visible part of the grid
+        let output = ScrollArea::both()
+            .auto_shrink(false)
+            .show(ui, |ui| paint_grid(ui));
+        Rect::from_min_size(
+            output.inner_rect.min,
+            output.content_size.min(output.inner_rect.size()),
+        )
+    };
+
+    GridResponse {
+        rect: Some(rect),
+        pins_changed,
+        blocked_times_changed,
+    }
+}
+
+/// Whole minutes since midnight as a time, clamped to the day
+pub fn time_from_minutes(minutes: u32) -> NaiveTime {
+    NaiveTime::from_num_seconds_from_midnight_opt((minutes * 60).min(86_399), 0)
+        .unwrap_or(NaiveTime::MIN)
+}
+
+/// Returns true if the record's group got pinned or unpinned
+fn render_pin_menu(ui: &mut egui::Ui, record: &CourseRecord) -> bool {
+    let mut definition = record.course_definition.borrow_mut();
+    let is_pinned = definition.pinned_groups.get(&record.record_type) == Some(&record.group);
+
+    let label = if is_pinned {
+        format!("Unpin {:?} G{}", record.record_type, record.group)
+    } else {
+        format!("Pin {:?} G{}", record.record_type, record.group)
+    };
+
+    if !ui.button(label).clicked() {
+        return false;
+    }
+
+    if is_pinned {
+        definition.pinned_groups.remove(&record.record_type);
+    } else {
+        definition
+            .pinned_groups
+            .insert(record.record_type, record.group);
+    }
+
+    ui.close();
+    true
+}
+
+fn paint_block(
+    painter: &egui::Painter,
+    block_rect: Rect,
+    record: &CourseRecord,
+    is_conflicting: bool,
+) {
+    let code = record.course_definition.borrow().code.clone();
+
+    painter.rect(
+        block_rect,
+        CornerRadius::same(4),
+        course_color(&code),
+        if is_conflicting {
+            Stroke::new(CONFLICT_STROKE_WIDTH, CONFLICT_COLOR)
+        } else {
+            Stroke::NONE
+        },
+        StrokeKind::Inside,
+    );
+
+    // Seat availability in the top right corner
+    if let Some(color) = availability_color(record.availability()) {
+        let center = block_rect.right_top()
+            + Vec2::new(
+                -(BLOCK_TEXT_PADDING + AVAILABILITY_DOT_RADIUS),
+                BLOCK_TEXT_PADDING + AVAILABILITY_DOT_RADIUS,
+            );
+        painter.circle(
+            center,
+            AVAILABILITY_DOT_RADIUS,
+            color,
+            Stroke::new(1.0, Color32::WHITE),
+        );
+    }
+
+    // Keep text inside the block
+    let painter = painter.with_clip_rect(block_rect.shrink(BLOCK_TEXT_PADDING * 0.5));
+    let lines = [
+        (code, FontId::proportional(12.5)),
+        (
+            format!("{:?} G{}", record.record_type, record.group),
+            FontId::proportional(11.0),
+        ),
+        (record.location.room_label(), FontId::proportional(10.0)),
+    ];
+
+    let mut text_pos = block_rect.left_top() + Vec2::splat(BLOCK_TEXT_PADDING);
+    for (text, font_id) in lines {
+        let galley = painter.layout_no_wrap(text, font_id, Color32::WHITE);
+        let line_height = galley.size().y;
+        painter.galley(text_pos, galley, Color32::WHITE);
+        text_pos.y += line_height;
+    }
+}
+
+fn describe_record(record: &CourseRecord, time_format: TimeFormat) -> String {
+    let definition = record.course_definition.borrow();
+    format!(
+        "{} - {}\n{:?} group {}\n{} - {}\n{}\n{}",
+        definition.code,
+        definition.name,
+        record.record_type,
+        record.group,
+        time_format.format(record.start_time),
+        time_format.format(record.end_time),
+        record.location,
+        record.availability()
+    )
+}
+
+/// Stable color per course code
+fn course_color(code: &str) -> Color32 {
+    let hash = code
+        .bytes()
+        .fold(0u32, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u32));
+    let hue = (hash % 360) as f32 / 360.0;
+
+    Hsva::new(hue, 0.45, 0.55, 1.0).into()
+}
+
+fn to_minutes(time: NaiveTime) -> f32 {
+    time.num_seconds_from_midnight() as f32 / 60.0
+}
@@ -0,0 +1,553 @@
+use super::View;
+use crate::{
+    CrynContext,
+    models::{CourseRecord, CourseRecordType, CourseSpan, OrderedWeekday},
+    services::{
+        BlockedConflict, GenerationJob, RecordConflict, Schedule, ScheduleDiagnosis,
+        ScheduleProblem, TightTransfer, find_blocked_conflicts, find_conflicts,
+        find_tight_transfers, score_schedule,
+    },
+    utils,
+    views::CoursesView,
+    windows::{MainWindow, Window, main_window::CONTENT_PADDING},
+};
+use egui::{
+    Align, Frame, Label, Layout, RichText, Sense, SidePanel, TopBottomPanel, epaint::MarginF32,
+};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+    sync::Arc,
+};
+
+mod blocked_times;
+mod diagnosis_panel;
+mod export_menu;
+pub mod grid;
+mod preferences_menu;
+
+use blocked_times::{BlockedDrag, BlockedTimesLayer};
+use diagnosis_panel::render_diagnosis_panel;
+use export_menu::render_export_menu;
+use preferences_menu::render_preferences_menu;
+
+const SCHEDULE_BAR_HEIGHT: f32 = 35.0;
+const DIAGNOSIS_PANEL_WIDTH: f32 = 280.0;
+
+/// Best ones kept out of everything generated
+const MAX_SCHEDULES: usize = 1000;
+
+pub struct TimeTableView {
+    span_map: BTreeMap<OrderedWeekday, CourseSpan>,
+    schedule_problem: Arc<ScheduleProblem>,
+
+    /// Best found so far, best first
+    schedules: Vec<Schedule>,
+
+    /// Every schedule generated, `schedules` only keeps the best
+    found_schedules: usize,
+
+    /// Running generation, replacing it cancels the old one
+    generation: Option<GenerationJob>,
+
+    /// Why generation found nothing, only after a full search
+    diagnosis: Option<ScheduleDiagnosis>,
+    diagnosis_clashes: Vec<RecordConflict>,
+    conflicts: Vec<RecordConflict>,
+    transfers: Vec<TightTransfer>,
+    blocked_conflicts: Vec<BlockedConflict>,
+
+    /// Busy times can be drawn on the grid
+    editing_blocked: bool,
+    blocked_drag: Option<BlockedDrag>,
+
+    /// None shows every group of the selected courses
+    current_schedule_idx: Option<usize>,
+
+    /// Records currently on the grid
+    shown_records: Vec<Rc<RefCell<CourseRecord>>>,
+
+    /// Shown courses and their groups, printed above screenshots
+    shown_groups: Vec<String>,
+}
+
+impl TimeTableView {
+    pub fn new() -> Self {
+        Self {
+            span_map: BTreeMap::new(),
+            schedule_problem: Arc::new(ScheduleProblem {
+                slots: Vec::new(),
+                blocked_times: Vec::new(),
+            }),
+            schedules: Vec::new(),
+            found_schedules: 0,
+            generation: None,
+            diagnosis: None,
+            diagnosis_clashes: Vec::new(),
+            conflicts: Vec::new(),
+            transfers: Vec::new(),
+            blocked_conflicts: Vec::new(),
+            editing_blocked: false,
+            blocked_drag: None,
+            current_schedule_idx: None,
+            shown_records: Vec::new(),
+            shown_groups: Vec::new(),
+        }
+    }
+
+    fn rebuild_schedules(&mut self, app_ctx: &CrynContext) {
+        self.schedule_problem = Arc::new(ScheduleProblem::from_course_manager(
+            &app_ctx.course_manager.borrow(),
+            app_ctx.schedule_preferences.borrow().group_filter,
+            &app_ctx.settings.borrow().blocked_times,
+        ));
+        self.schedules.clear();
+        self.current_schedule_idx = None;
+        self.start_generation(app_ctx);
+    }
+
+    /// Generates and ranks from scratch, the old schedules stay up until the new ones come in
+    fn start_generation(&mut self, app_ctx: &CrynContext) {
+        self.diagnosis = None;
+        self.diagnosis_clashes.clear();
+        self.generation = Some(GenerationJob::start(
+            Arc::clone(&self.schedule_problem),
+            app_ctx.schedule_preferences.borrow().clone(),
+            app_ctx.settings.borrow().campus_map.clone(),
+            MAX_SCHEDULES,
+        ));
+        self.poll_generation(app_ctx);
+    }
+
+    /// Takes in the best schedules so far, returns true if the shown schedule changed
+    fn poll_generation(&mut self, app_ctx: &CrynContext) -> bool {
+        let Some(generation) = &mut self.generation else {
+            return false;
+        };
+
+        let ranked = generation.poll();
+        self.found_schedules = generation.found();
+
+        if generation.is_done() {
+            self.diagnosis = generation.take_diagnosis();
+            self.generation = None;
+
+            if let Some(diagnosis) = &self.diagnosis {
+                self.diagnosis_clashes =
+                    diagnosis.clashes(&self.schedule_problem, &app_ctx.course_manager.borrow());
+            }
+        }
+
+        let Some(ranked) = ranked else {
+            return false;
+        };
+
+        // Ranks move around while generating, the chosen schedule moves along
+        let shown = self
+            .current_schedule_idx
+            .map(|idx| self.schedules[idx].clone());
+        self.schedules = ranked;
+        self.restore_chosen_schedule(app_ctx);
+
+        shown.as_ref() != self.current_schedule_idx.map(|idx| &self.schedules[idx])
+    }
+
+    /// Finds the previously picked schedule, falls back to every group
+    fn restore_chosen_schedule(&mut self, app_ctx: &CrynContext) {
+        let chosen_groups = app_ctx.chosen_groups.borrow();
+        self.current_schedule_idx = if chosen_groups.is_empty() {
+            None
+        } else {
+            self.schedules.iter().position(|schedule| {
+                schedule.saved_groups(&self.schedule_problem) == *chosen_groups
+            })
+        };
+    }
+
+    fn store_chosen_schedule(&self, app_ctx: &CrynContext) {
+        *app_ctx.chosen_groups.borrow_mut() = match self.current_schedule_idx {
+            Some(idx) => self.schedules[idx].saved_groups(&self.schedule_problem),
+            None => Vec::new(),
+        };
+    }
+
+    fn rebuild_span_map(&mut self, app_ctx: &CrynContext) {
+        let course_manager = app_ctx.course_manager.borrow();
+        let available_records = match self.current_schedule_idx {
+            Some(idx) => self.schedules[idx]
+                .record_indices(&self.schedule_problem)
+                .into_iter()
+                .map(|record_idx| Rc::clone(&course_manager.course_records[record_idx]))
+                .collect(),
+            None => course_manager.get_available_course_records(),
+        };
+
+        self.conflicts = find_conflicts(&available_records);
+        self.transfers =
+            find_tight_transfers(&available_records, &app_ctx.settings.borrow().campus_map);
+        self.blocked_conflicts =
+            find_blocked_conflicts(&available_records, &app_ctx.settings.borrow().blocked_times);
+
+        // Summary for screenshots
+        let mut groups_by_course =
+            BTreeMap::<String, (String, BTreeSet<(CourseRecordType, i32)>)>::new();
+        available_records.iter().for_each(|record| {
+            let record = record.borrow();
+            let definition = record.course_definition.borrow();
+            groups_by_course
+                .entry(definition.code.clone())
+                .or_insert_with(|| (definition.name.clone(), BTreeSet::new()))
+                .1
+                .insert((record.record_type, record.group));
+        });
+        self.shown_groups = groups_by_course
+            .into_iter()
+            .map(|(code, (name, groups))| {
+                let groups: Vec<String> = groups
+                    .into_iter()
+                    .map(|(record_type, group)| format!("{:?} G{}", record_type, group))
+                    .collect();
+                format!("{} {} - {}", code, name, groups.join(", "))
+            })
+            .collect();
+
+        self.span_map.clear();
+        available_records.iter().for_each(|record| {
+            self.span_map
+                .entry(record.borrow().day.into())
+                .or_default()
+                .insert_course_record(record);
+        });
+
+        self.shown_records = available_records;
+    }
+
+    /// Header only drawn on the frame we capture
+    fn render_capture_header(&self, ui: &mut egui::Ui, app_ctx: &CrynContext) -> egui::Rect {
+        Frame::new()
+            .inner_margin(MarginF32::same(CONTENT_PADDING))
+            .fill(ui.visuals().window_fill)
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+
+                ui.heading(&app_ctx.course_manager.borrow().source_name);
+                self.shown_groups.iter().for_each(|line| {
+                    ui.label(line);
+                });
+            })
+            .response
+            .rect
+    }
+
+    /// Returns true if the shown schedule changed
+    fn render_schedule_bar(&mut self, ui: &mut egui::Ui, app_ctx: &CrynContext) -> bool {
+        let mut target_schedule_idx = self.current_schedule_idx;
+        let mut preferences_changed = false;
+        let group_filter = app_ctx.schedule_preferences.borrow().group_filter;
+        let time_format = app_ctx.settings.borrow().time_format;
+
+        TopBottomPanel::top("timetable_view_top_panel")
+            .frame(
+                Frame::new()
+                    .inner_margin(MarginF32::symmetric(CONTENT_PADDING, 0.0))
+                    .fill(ui.visuals().faint_bg_color),
+            )
+            .exact_height(SCHEDULE_BAR_HEIGHT)
+            .show_inside(ui, |ui| {
+                ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
+                    if ui
+                        .selectable_label(target_schedule_idx.is_none(), "All groups")
+                        .clicked()
+                    {
+                        target_schedule_idx = None;
+                    }
+
+                    if !self.conflicts.is_empty() {
+                        ui.colored_label(
+                            grid::CONFLICT_COLOR,
+                            format!("{} conflicts", self.conflicts.len()),
+                        );
+                    }
+
+                    if !self.blocked_conflicts.is_empty() {
+                        ui.colored_label(
+                            grid::CONFLICT_COLOR,
+                            format!("{} during busy times", self.blocked_conflicts.len()),
+                        )
+                        .on_hover_ui(|ui| {
+                            self.blocked_conflicts.iter().for_each(|conflict| {
+                                ui.label(conflict.describe(time_format));
+                            });
+                        });
+                    }
+
+                    if !self.transfers.is_empty() {
+                        ui.colored_label(
+                            grid::TRANSFER_COLOR,
+                            format!("{} rushed walks", self.transfers.len()),
+                        )
+                        .on_hover_ui(|ui| {
+                            self.transfers.iter().for_each(|transfer| {
+                                ui.label(transfer.describe(time_format));
+                            });
+                        });
+                    }
+
+                    ui.separator();
+
+                    if let Some(generation) = &mut self.generation {
+                        ui.spinner();
+                        ui.label(format!(
+                            "{} found, {:.0}%",
+                            generation.found(),
+                            generation.progress() * 100.0
+                        ));
+
+                        if ui
+                            .small_button("Stop")
+                            .on_hover_text("Keep the schedules found so far")
+                            .clicked()
+                        {
+                            generation.cancel();
+                        }
+
+                        ui.separator();
+                    }
+
+                    if self.schedules.is_empty() {
+                        if self.generation.is_none() && self.diagnosis.is_none() {
+                            ui.weak("No conflict-free schedule");
+                        }
+                    } else {
+                        self.render_schedule_navigator(ui, app_ctx, &mut target_schedule_idx);
+                    }
+
+                    // Ranking preferences on the far right
+                    ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                        ui.menu_button("Preferences", |ui| {
+                            let preferences = &mut *app_ctx.schedule_preferences.borrow_mut();
+                            let week_start = app_ctx.settings.borrow().week_start;
+                            preferences_changed =
+                                render_preferences_menu(ui, preferences, week_start);
+                        });
+
+                        ui.menu_button("Export", |ui| {
+                            let semester = &mut app_ctx.saved_session.borrow_mut().semester;
+                            render_export_menu(ui, semester, &self.shown_records);
+                        });
+
+                        ui.toggle_value(&mut self.editing_blocked, "Edit busy times")
+                            .on_hover_text(
+                                "Drag on the grid to block time, right click a block to edit it",
+                            );
+                    });
+                });
+            });
+
+        // Filtered groups change the schedules themselves
+        if app_ctx.schedule_preferences.borrow().group_filter != group_filter {
+            self.rebuild_schedules(app_ctx);
+            return true;
+        }
+
+        // Different schedules make the cut, polling swaps them in
+        if preferences_changed {
+            self.start_generation(app_ctx);
+        }
+
+        let changed = target_schedule_idx != self.current_schedule_idx;
+        self.current_schedule_idx = target_schedule_idx;
+        changed
+    }
+
+    /// Flipping through the schedules and the shown one's score
+    fn render_schedule_navigator(
+        &self,
+        ui: &mut egui::Ui,
+        app_ctx: &CrynContext,
+        target_schedule_idx: &mut Option<usize>,
+    ) {
+        let schedule_count = self.schedules.len();
+        let current_idx = target_schedule_idx.unwrap_or(0);
+
+        if ui.button("<").clicked() {
+            *target_schedule_idx = Some(match *target_schedule_idx {
+                Some(idx) => (idx + schedule_count - 1) % schedule_count,
+                None => 0,
+            });
+        }
+
+        ui.label(match *target_schedule_idx {
+            Some(_) => format!("Schedule {} of {}", current_idx + 1, schedule_count),
+            None if self.found_schedules > schedule_count => {
+                format!(
+                    "Best {} of {} schedules",
+                    schedule_count, self.found_schedules
+                )
+            }
+            None => format!("{} schedules", schedule_count),
+        });
+
+        if ui.button(">").clicked() {
+            *target_schedule_idx = Some(match *target_schedule_idx {
+                Some(idx) => (idx + 1) % schedule_count,
+                None => 0,
+            });
+        }
+
+        // How did the shown schedule score?
+        if let Some(idx) = *target_schedule_idx {
+            let score = score_schedule(
+                &self.schedule_problem,
+                &self.schedules[idx],
+                &app_ctx.schedule_preferences.borrow(),
+                &app_ctx.settings.borrow().campus_map,
+            );
+
+            ui.separator();
+            ui.weak(format!(
+                "{} days, {}h {}m gaps, {} early",
+                score.campus_days,
+                score.gap_minutes / 60,
+                score.gap_minutes % 60,
+                score.early_sessions
+            ));
+
+            if score.blocked_minutes > 0 {
+                ui.weak(format!("{}m during busy times", score.blocked_minutes));
+            }
+        }
+    }
+}
+
+impl Default for TimeTableView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl View for TimeTableView {
+    fn name(&self) -> &str {
+        "Time Table"
+    }
+
+    fn on_show(&mut self, app_ctx: &CrynContext) {
+        self.rebuild_schedules(app_ctx);
+        self.rebuild_span_map(app_ctx);
+    }
+
+    fn on_hide(&mut self, _app_ctx: &CrynContext) {
+        // Selection can change while hidden, on_show starts over anyway
+        self.generation = None;
+    }
+
+    fn on_gui(&mut self, ui: &mut egui::Ui, app_ctx: &CrynContext, window: &mut dyn Window) {
+        // New schedules stream in while generation runs
+        if self.generation.is_some() {
+            ui.ctx().request_repaint();
+        }
+
+        if self.poll_generation(app_ctx) {
+            self.rebuild_span_map(app_ctx);
+        }
+
+        // hmmm
+        // elnas 3yza eh
+        // elnas bt3ml eh
+        // 3en safra wltanya 7amra
+        // ololy a3ml eh
+        // 3en safra wltanya khadra ;)
+        // ololy a3ml ehhhhhh
+
+        let has_blocked_times = !app_ctx.settings.borrow().blocked_times.is_empty();
+        if self.span_map.is_empty() && !has_blocked_times && !self.editing_blocked {
+            // Nothing to capture
+            *app_ctx.screenshot_requested.borrow_mut() = false;
+            utils::cancel_screenshot(ui.ctx());
+
+            ui.centered_and_justified(|ui| {
+                if ui
+                    .add(
+                        Label::new(RichText::new("Select courses to start").heading())
+                            .sense(Sense::click()),
+                    )
+                    .on_hover_cursor(egui::CursorIcon::PointingHand)
+                    .clicked()
+                {
+                    // Go to courses view
+                    if let Some(main_window) = window.as_any_mut().downcast_mut::<MainWindow>() {
+                        main_window.switch_to_view::<CoursesView>(app_ctx);
+                    }
+                }
+            });
+            return;
+        }
+
+        if self.render_schedule_bar(ui, app_ctx) {
+            self.store_chosen_schedule(app_ctx);
+            self.rebuild_span_map(app_ctx);
+        }
+
+        if let Some(diagnosis) = &self.diagnosis {
+            let deselected = SidePanel::right("timetable_diagnosis_panel")
+                .resizable(true)
+                .default_width(DIAGNOSIS_PANEL_WIDTH)
+                .show_inside(ui, |ui| {
+                    let time_format = app_ctx.settings.borrow().time_format;
+                    render_diagnosis_panel(ui, diagnosis, &self.diagnosis_clashes, time_format)
+                })
+                .inner;
+
+            if let Some(code) = deselected {
+                app_ctx
+                    .course_manager
+                    .borrow()
+                    .course_definitions
+                    .iter()
+                    .filter(|def| def.borrow().code == code)
+                    .for_each(|def| def.borrow_mut().selected = false);
+
+                self.rebuild_schedules(app_ctx);
+                self.rebuild_span_map(app_ctx);
+            }
+        }
+
+        // Screenshot button, header and grid go into the capture
+        let is_capturing = std::mem::take(&mut *app_ctx.screenshot_requested.borrow_mut());
+        let header_rect = is_capturing.then(|| self.render_capture_header(ui, app_ctx));
+
+        let grid = {
+            let settings = &mut *app_ctx.settings.borrow_mut();
+            let mut blocked = BlockedTimesLayer {
+                times: &mut settings.blocked_times,
+                conflicts: &self.blocked_conflicts,
+                editing: self.editing_blocked,
+                drag: &mut self.blocked_drag,
+                time_format: settings.time_format,
+            };
+
+            grid::render_grid(
+                ui,
+                &self.span_map,
+                &self.conflicts,
+                &self.transfers,
+                &mut blocked,
+                settings.week_start,
+                is_capturing,
+            )
+        };
+        if let (Some(header_rect), Some(grid_rect)) = (header_rect, grid.rect) {
+            // Didnt fit, the ui got zoomed out so try again next frame
+            if !utils::request_screenshot(ui.ctx(), header_rect.union(grid_rect)) {
+                *app_ctx.screenshot_requested.borrow_mut() = true;
+            }
+        }
+
+        // Pinned groups and hard busy times narrow down the schedules
+        if grid.pins_changed || grid.blocked_times_changed {
+            self.rebuild_schedules(app_ctx);
+            self.rebuild_span_map(app_ctx);
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::models::{OrderedWeekday, SchedulePreferences};
+use chrono::{NaiveTime, Timelike, Weekday};
+use egui::{ComboBox, Slider};
+
+/// Returns true if any preference changed
+pub fn render_preferences_menu(
+    ui: &mut egui::Ui,
+    preferences: &mut SchedulePreferences,
+    week_start: Weekday,
+) -> bool {
+    let mut changed = false;
+
+    ui.strong("Rank schedules by");
+
+    changed |= ui
+        .add(Slider::new(&mut preferences.campus_days_weight, 0.0..=20.0).text("Fewer campus days"))
+        .changed();
+
+    changed |= ui
+        .add(Slider::new(&mut preferences.gaps_weight, 0.0..=20.0).text("Fewer gaps"))
+        .changed();
+
+    changed |= ui
+        .add(Slider::new(&mut preferences.compactness_weight, 0.0..=20.0).text("Shorter days"))
+        .changed();
+
+    changed |= ui
+        .add(
+            Slider::new(&mut preferences.tight_transfer_weight, 0.0..=50.0)
+                .text("Fewer rushed walks"),
+        )
+        .on_hover_text("Breaks too short to get to another campus or building, see Settings")
+        .changed();
+
+    changed |= ui
+        .add(
+            Slider::new(&mut preferences.blocked_time_weight, 0.0..=50.0)
+                .text("Respect busy times"),
+        )
+        .on_hover_text("Per hour of class during a soft busy time, hard ones are never overlapped")
+        .changed();
+
+    ui.separator();
+
+    // Earliest start
+    let mut has_earliest_start = preferences.earliest_start.is_some();
+    if ui
+        .checkbox(&mut has_earliest_start, "Avoid early sessions")
+        .changed()
+    {
+        preferences.earliest_start = has_earliest_start
+            .then(|| SchedulePreferences::default().earliest_start)
+            .flatten();
+        changed = true;
+    }
+
+    if let Some(earliest_start) = preferences.earliest_start {
+        let mut hour = earliest_start.hour();
+        if ui
+            .add(
+                Slider::new(&mut hour, 8..=14)
+                    .text("Not before")
+                    .suffix(":00"),
+            )
+            .changed()
+        {
+            preferences.earliest_start = NaiveTime::from_hms_opt(hour, 0, 0);
+            changed = true;
+        }
+
+        changed |= ui
+            .add(Slider::new(&mut preferences.early_start_weight, 0.0..=20.0).text("Weight"))
+            .changed();
+    }
+
+    ui.separator();
+
+    // Free day
+    ComboBox::from_label("Free day")
+        .selected_text(preferences.free_day.map_or("None".to_owned(), |day| {
+            OrderedWeekday::from(day).to_string()
+        }))
+        .show_ui(ui, |ui| {
+            changed |= ui
+                .selectable_value(&mut preferences.free_day, None, "None")
+                .changed();
+
+            // Friday is off already
+            for day in OrderedWeekday::week(week_start).filter(|day| *day != Weekday::Fri) {
+                changed |= ui
+                    .selectable_value(
+                        &mut preferences.free_day,
+                        Some(day),
+                        OrderedWeekday::from(day).to_string(),
+                    )
+                    .changed();
+            }
+        });
+
+    if preferences.free_day.is_some() {
+        changed |= ui
+            .add(Slider::new(&mut preferences.free_day_weight, 0.0..=50.0).text("Weight"))
+            .changed();
+    }
+
+    ui.separator();
+
+    // Availability
+    ui.strong("Leave out groups that are");
+    changed |= ui
+        .checkbox(&mut preferences.group_filter.skip_closed, "Closed")
+        .changed();
+    changed |= ui
+        .checkbox(
+            &mut preferences.group_filter.skip_full,
+            "Full or waitlisted",
+        )
+        .changed();
+
+    changed
+}
@@ -1,6 +1,7 @@
 use crate::{
     CrynContext,
-    views::{CoursesView, PlaceholderView, TimeTableView, View},
+    utils::PickedFile,
+    views::{ChangesView, CoursesView, SettingsView, TimeTableView, View},
     windows::Window,
 };
 use egui::{CentralPanel, Frame, epaint::MarginF32};
@@ -10,8 +11,13 @@ use std::{
 };
 
 mod nav_bar;
+mod paste_dialog;
+mod spreadsheet_dialog;
 mod title_bar;
 
+use paste_dialog::{PasteDialog, PasteDialogResult};
+use spreadsheet_dialog::{SpreadsheetDialog, SpreadsheetDialogResult};
+
 #[cfg(not(target_arch = "wasm32"))]
 mod desktop;
 
@@ -30,6 +36,9 @@ pub struct MainWindow {
 
     /// Safe guard to prevent switching views while rendering content
     is_rendering_content: bool,
+
+    paste_dialog: Option<PasteDialog>,
+    spreadsheet_dialog: Option<SpreadsheetDialog>,
 }
 
 impl MainWindow {
@@ -39,12 +48,15 @@ impl MainWindow {
             current_view_id: None,
             requested_target_view_id: None,
             is_rendering_content: false,
+            paste_dialog: None,
+            spreadsheet_dialog: None,
         };
 
         // Register views
         window.register_view(TimeTableView::new());
         window.register_view(CoursesView::new());
-        window.register_view(PlaceholderView);
+        window.register_view(ChangesView::new());
+        window.register_view(SettingsView::new());
 
         // TT view by def
         window.switch_to_view::<TimeTableView>(app_ctx);
@@ -93,6 +105,79 @@ impl MainWindow {
         target_view.on_show(app_ctx);
     }
 
+    pub fn switch_to_view_by_name(&mut self, name: &str, app_ctx: &CrynContext) {
+        let target_id = self
+            .views
+            .iter()
+            .find(|(_, view)| view.name() == name)
+            .map(|(id, _)| *id);
+
+        if let Some(target_id) = target_id {
+            self.switch_to_view_internal(target_id, app_ctx);
+        }
+    }
+
+    pub fn current_view_name(&self) -> Option<String> {
+        self.current_view_id
+            .and_then(|id| self.views.get(&id))
+            .map(|view| view.name().to_owned())
+    }
+
+    /// Re-runs the current view callbacks, ie after new data was loaded
+    pub fn refresh_current_view(&mut self, app_ctx: &CrynContext) {
+        if let Some(view) = self.get_current_view() {
+            view.on_hide(app_ctx);
+            view.on_show(app_ctx);
+        }
+    }
+
+    pub fn open_paste_dialog(&mut self) {
+        self.paste_dialog = Some(PasteDialog::new());
+    }
+
+    fn render_paste_dialog(&mut self, ctx: &egui::Context, app_ctx: &CrynContext) {
+        let Some(paste_dialog) = &mut self.paste_dialog else {
+            return;
+        };
+
+        match paste_dialog.show(ctx, app_ctx) {
+            PasteDialogResult::Open => {}
+            PasteDialogResult::Closed => self.paste_dialog = None,
+            PasteDialogResult::Imported => {
+                self.paste_dialog = None;
+                self.refresh_current_view(app_ctx);
+            }
+        }
+    }
+
+    /// Spreadsheets need their columns mapped before anything is imported
+    pub fn open_spreadsheet_dialog(
+        &mut self,
+        file: &PickedFile,
+        app_ctx: &CrynContext,
+    ) -> Result<(), String> {
+        let dialog = SpreadsheetDialog::new(file, app_ctx)
+            .map_err(|err| format!("Failed to load {}: {}", file.name, err))?;
+        self.spreadsheet_dialog = Some(dialog);
+
+        Ok(())
+    }
+
+    fn render_spreadsheet_dialog(&mut self, ctx: &egui::Context, app_ctx: &CrynContext) {
+        let Some(spreadsheet_dialog) = &mut self.spreadsheet_dialog else {
+            return;
+        };
+
+        match spreadsheet_dialog.show(ctx, app_ctx) {
+            SpreadsheetDialogResult::Open => {}
+            SpreadsheetDialogResult::Closed => self.spreadsheet_dialog = None,
+            SpreadsheetDialogResult::Imported => {
+                self.spreadsheet_dialog = None;
+                self.refresh_current_view(app_ctx);
+            }
+        }
+    }
+
     pub fn request_switch_to_view<V: View + 'static>(&mut self) {
         self.requested_target_view_id = Some(TypeId::of::<V>());
     }
@@ -103,7 +188,7 @@ impl MainWindow {
         desktop::handle_resize_events(ctx);
 
         // Title bar and window controls
-        title_bar::render_title_bar(ctx, self.get_current_view().as_deref());
+        title_bar::render_title_bar(ctx, app_ctx, self.get_current_view().map(|v| &**v));
 
         // Nav bar
         nav_bar::render_nav_bar(self, ctx, app_ctx);
@@ -113,6 +198,10 @@ impl MainWindow {
         self.render_content(ctx, app_ctx);
         self.is_rendering_content = false;
 
+        // Dialogs on top
+        self.render_paste_dialog(ctx, app_ctx);
+        self.render_spreadsheet_dialog(ctx, app_ctx);
+
         // Handle post-render requested view switch
         if let Some(target_view_id) = self.requested_target_view_id {
             self.switch_to_view_internal(target_view_id, app_ctx);
@@ -160,11 +249,7 @@ impl MainWindow {
     }
 
     fn get_current_view(&mut self) -> Option<&mut Box<dyn View>> {
-        let current_view_id = self
-            .current_view_id
-            .unwrap_or(TypeId::of::<PlaceholderView>());
-
-        self.views.get_mut(&current_view_id)
+        self.views.get_mut(&self.current_view_id?)
     }
 }
 
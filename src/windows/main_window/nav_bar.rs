@@ -2,16 +2,19 @@ use std::any::TypeId;
 
 use super::{MainWindow, NAVBAR_HEIGHT};
 use crate::{
-    CrynContext,
-    views::{CoursesView, PlaceholderView, TimeTableView, View},
+    CrynContext, utils,
+    views::{ChangesView, CoursesView, SettingsView, TimeTableView, View},
 };
 
 // Navbar characters
 // segmdl2.ttf
 const ICON_CALENDAR: &str = "\u{E787}";
 const ICON_LIBRARY: &str = "\u{E8F1}";
+const ICON_HISTORY: &str = "\u{E81C}";
 const ICON_SETTINGS: &str = "\u{E713}";
 const ICON_SCREENSHOT: &str = "\u{E158}";
+const ICON_OPEN: &str = "\u{E8E5}";
+const ICON_PASTE: &str = "\u{E77F}";
 
 pub fn render_nav_bar(main_window: &mut MainWindow, ctx: &egui::Context, app_ctx: &CrynContext) {
     let button_width = (ctx.content_rect().width() / 8.0).clamp(100.0, 150.0);
@@ -59,10 +62,19 @@ pub fn render_nav_bar(main_window: &mut MainWindow, ctx: &egui::Context, app_ctx
                         "Courses",
                         button_width,
                     );
+                    render_button_view::<ChangesView>(
+                        main_window,
+                        app_ctx,
+                        ctx,
+                        ui,
+                        ICON_HISTORY,
+                        "Changes",
+                        button_width,
+                    );
 
                     // Right side buttons
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        render_button_view::<PlaceholderView>(
+                        render_button_view::<SettingsView>(
                             main_window,
                             app_ctx,
                             ctx,
@@ -79,7 +91,40 @@ pub fn render_nav_bar(main_window: &mut MainWindow, ctx: &egui::Context, app_ctx
                             ICON_SCREENSHOT,
                             "Screenshot",
                             button_width,
-                            Some(|_: &mut MainWindow| {}),
+                            Some(|mw: &mut MainWindow| {
+                                // Captured by the time table on its next frame
+                                mw.switch_to_view::<TimeTableView>(app_ctx);
+                                *app_ctx.screenshot_requested.borrow_mut() = true;
+                            }),
+                            Some(false),
+                        );
+
+                        render_button(
+                            main_window,
+                            ctx,
+                            ui,
+                            ICON_OPEN,
+                            "Open",
+                            button_width,
+                            Some(|_: &mut MainWindow| {
+                                let pending_file = utils::pick_file(
+                                    ctx,
+                                    "Course data",
+                                    &["txt", "html", "htm", "json", "csv", "xlsx", "xls", "ods"],
+                                );
+                                *app_ctx.pending_file.borrow_mut() = Some(pending_file);
+                            }),
+                            Some(false),
+                        );
+
+                        render_button(
+                            main_window,
+                            ctx,
+                            ui,
+                            ICON_PASTE,
+                            "Paste",
+                            button_width,
+                            Some(|mw: &mut MainWindow| mw.open_paste_dialog()),
                             Some(false),
                         );
                     });
@@ -111,6 +156,7 @@ fn render_button_view<V: View + 'static>(
     );
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_button(
     main_window: &mut MainWindow,
     ctx: &egui::Context,
@@ -161,9 +207,9 @@ fn render_button(
         ctx.set_cursor_icon(egui::CursorIcon::PointingHand);
     }
 
-    if response.clicked() {
-        if let Some(on_click) = on_click {
-            on_click(main_window);
-        }
+    if response.clicked()
+        && let Some(on_click) = on_click
+    {
+        on_click(main_window);
     }
 }
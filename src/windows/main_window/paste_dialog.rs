@@ -0,0 +1,132 @@
+use crate::{
+    CrynContext,
+    services::{CourseManager, ParseError, ParseInput},
+};
+use egui::{Align, Layout, Modal, RichText, ScrollArea};
+
+const PASTED_SOURCE_NAME: &str = "Pasted data";
+
+/// How much of the pasted text we echo back
+const PREVIEW_CHAR_COUNT: usize = 1500;
+
+const DIALOG_WIDTH: f32 = 560.0;
+const PREVIEW_HEIGHT: f32 = 180.0;
+
+pub enum PasteDialogResult {
+    Open,
+    Closed,
+    Imported,
+}
+
+/// Paste the portal response, preview it, then import
+pub struct PasteDialog {
+    text: String,
+    staged: Option<Result<CourseManager, ParseError>>,
+}
+
+impl PasteDialog {
+    pub fn new() -> Self {
+        Self {
+            text: String::new(),
+            staged: None,
+        }
+    }
+
+    fn set_text(&mut self, text: String, app_ctx: &CrynContext) {
+        // Parse right away so we can preview counts
+        self.staged =
+            Some(app_ctx.stage_courses(&ParseInput::from_text(PASTED_SOURCE_NAME, &text)));
+        self.text = text;
+    }
+
+    fn capture_paste(&mut self, ctx: &egui::Context, app_ctx: &CrynContext) {
+        let pasted = ctx.input(|i| {
+            i.events.iter().rev().find_map(|event| match event {
+                egui::Event::Paste(text) => Some(text.clone()),
+                _ => None,
+            })
+        });
+
+        if let Some(pasted) = pasted.filter(|text| !text.trim().is_empty()) {
+            self.set_text(pasted, app_ctx);
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, app_ctx: &CrynContext) -> PasteDialogResult {
+        self.capture_paste(ctx, app_ctx);
+
+        let mut result = PasteDialogResult::Open;
+        let modal = Modal::new(egui::Id::new("paste_dialog")).show(ctx, |ui| {
+            ui.set_width(DIALOG_WIDTH);
+
+            ui.heading("Paste course data");
+            ui.label(
+                "Copy the portal response from the browser's network tab, then press Ctrl+V here.",
+            );
+            ui.add_space(8.0);
+
+            // Echo what we got
+            ScrollArea::vertical()
+                .max_height(PREVIEW_HEIGHT)
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    if self.text.is_empty() {
+                        ui.weak("Nothing pasted yet");
+                    } else {
+                        let preview: String = self.text.chars().take(PREVIEW_CHAR_COUNT).collect();
+                        ui.label(RichText::new(preview).monospace().small());
+                    }
+                });
+
+            ui.separator();
+
+            // Preview counts
+            match &self.staged {
+                Some(Ok(staged)) => {
+                    ui.label(format!(
+                        "Found {} courses and {} records",
+                        staged.course_definitions.len(),
+                        staged.course_records.len()
+                    ));
+
+                    if !staged.parse_report.rejected.is_empty() {
+                        ui.weak(staged.parse_report.to_string());
+                    }
+                }
+                Some(Err(err)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, err.to_string());
+                }
+                None => {}
+            }
+
+            ui.add_space(8.0);
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                let can_import = matches!(self.staged, Some(Ok(_)));
+                if ui
+                    .add_enabled(can_import, egui::Button::new("Import"))
+                    .clicked()
+                    && let Some(Ok(staged)) = self.staged.take()
+                {
+                    app_ctx.replace_courses(staged, PASTED_SOURCE_NAME);
+
+                    result = PasteDialogResult::Imported;
+                }
+
+                if ui.button("Cancel").clicked() {
+                    result = PasteDialogResult::Closed;
+                }
+
+                if !self.text.is_empty() && ui.button("Clear").clicked() {
+                    self.text.clear();
+                    self.staged = None;
+                }
+            });
+        });
+
+        if modal.should_close() && matches!(result, PasteDialogResult::Open) {
+            result = PasteDialogResult::Closed;
+        }
+
+        result
+    }
+}
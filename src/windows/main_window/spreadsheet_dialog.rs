@@ -0,0 +1,280 @@
+use crate::{
+    CrynContext,
+    services::{
+        CourseColumn, CourseManager, ParseError, REQUIRED_SPREADSHEET_COLUMNS, SpreadsheetMapping,
+        Workbook,
+    },
+    utils::PickedFile,
+};
+use egui::{Align, ComboBox, DragValue, Grid, Layout, Modal, RichText, ScrollArea};
+
+const DIALOG_WIDTH: f32 = 620.0;
+const PREVIEW_HEIGHT: f32 = 140.0;
+
+/// Rows shown under the header in the preview
+const PREVIEW_ROW_COUNT: usize = 5;
+
+pub enum SpreadsheetDialogResult {
+    Open,
+    Closed,
+    Imported,
+}
+
+/// Lets the user point us at the right sheet columns, then imports
+pub struct SpreadsheetDialog {
+    file_name: String,
+    workbook: Workbook,
+    mapping: SpreadsheetMapping,
+    staged: Result<CourseManager, ParseError>,
+}
+
+impl SpreadsheetDialog {
+    pub fn new(file: &PickedFile, app_ctx: &CrynContext) -> Result<Self, ParseError> {
+        let workbook = Workbook::read(&file.bytes)?;
+
+        // Fall back to the first row of the first sheet when nothing fits
+        let mapping = SpreadsheetMapping::detect(&workbook)
+            .unwrap_or_else(|| Self::guess_mapping(&workbook, &workbook.sheets[0].0, 0));
+        let staged =
+            CourseManager::stage_spreadsheet(&workbook, &mapping, &app_ctx.parse_options());
+
+        Ok(Self {
+            file_name: file.name.clone(),
+            workbook,
+            mapping,
+            staged,
+        })
+    }
+
+    fn guess_mapping(workbook: &Workbook, sheet: &str, header_row: usize) -> SpreadsheetMapping {
+        let headers = workbook
+            .rows(sheet)
+            .and_then(|rows| rows.get(header_row))
+            .cloned()
+            .unwrap_or_default();
+
+        SpreadsheetMapping::guess(sheet, header_row, &headers)
+    }
+
+    fn headers(&self) -> Vec<String> {
+        self.workbook
+            .rows(&self.mapping.sheet)
+            .and_then(|rows| rows.get(self.mapping.header_row))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns true if the mapping changed
+    fn render_mapping(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        let headers = self.headers();
+
+        Grid::new("spreadsheet_mapping_grid")
+            .num_columns(4)
+            .spacing([12.0, 6.0])
+            .show(ui, |ui| {
+                for (idx, column) in CourseColumn::ALL.iter().enumerate() {
+                    let is_required = REQUIRED_SPREADSHEET_COLUMNS.contains(column);
+                    let label = if is_required {
+                        format!("{} *", column)
+                    } else {
+                        column.to_string()
+                    };
+                    ui.label(label);
+
+                    let selected = self.mapping.columns.get(column).copied();
+                    let selected_text =
+                        selected.map_or("Not mapped".to_owned(), |col| column_title(&headers, col));
+
+                    ComboBox::from_id_salt(("spreadsheet_column", idx))
+                        .width(150.0)
+                        .selected_text(selected_text)
+                        .show_ui(ui, |ui| {
+                            if ui
+                                .selectable_label(selected.is_none(), "Not mapped")
+                                .clicked()
+                            {
+                                self.mapping.columns.remove(column);
+                                changed = true;
+                            }
+
+                            for col in 0..headers.len() {
+                                if ui
+                                    .selectable_label(
+                                        selected == Some(col),
+                                        column_title(&headers, col),
+                                    )
+                                    .clicked()
+                                {
+                                    self.mapping.columns.insert(*column, col);
+                                    changed = true;
+                                }
+                            }
+                        });
+
+                    // Two pairs per row
+                    if idx % 2 == 1 {
+                        ui.end_row();
+                    }
+                }
+            });
+
+        changed
+    }
+
+    fn render_preview(&self, ui: &mut egui::Ui) {
+        let Some(rows) = self.workbook.rows(&self.mapping.sheet) else {
+            return;
+        };
+
+        ScrollArea::both()
+            .max_height(PREVIEW_HEIGHT)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                Grid::new("spreadsheet_preview_grid")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        let preview_rows = rows
+                            .iter()
+                            .skip(self.mapping.header_row)
+                            .take(PREVIEW_ROW_COUNT + 1);
+                        for (idx, row) in preview_rows.enumerate() {
+                            for cell in row {
+                                let text = RichText::new(cell).small();
+                                ui.label(if idx == 0 { text.strong() } else { text });
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, app_ctx: &CrynContext) -> SpreadsheetDialogResult {
+        let mut result = SpreadsheetDialogResult::Open;
+        let mut mapping_changed = false;
+
+        let modal = Modal::new(egui::Id::new("spreadsheet_dialog")).show(ctx, |ui| {
+            ui.set_width(DIALOG_WIDTH);
+
+            ui.heading(format!("Import {}", self.file_name));
+            ui.label("Tell us which sheet columns hold the course data.");
+            ui.add_space(8.0);
+
+            ui.horizontal(|ui| {
+                // Sheet
+                let mut sheet = self.mapping.sheet.clone();
+                ComboBox::from_label("Sheet")
+                    .selected_text(&sheet)
+                    .show_ui(ui, |ui| {
+                        for (name, _) in &self.workbook.sheets {
+                            ui.selectable_value(&mut sheet, name.clone(), name);
+                        }
+                    });
+
+                // Header row, shown 1 based like the spreadsheet apps
+                let mut header_row = self.mapping.header_row + 1;
+                ui.add_space(16.0);
+                ui.label("Header row");
+                ui.add(DragValue::new(&mut header_row).range(1..=usize::MAX));
+
+                // Re-guess when the layout moves under the mapping
+                if sheet != self.mapping.sheet || header_row - 1 != self.mapping.header_row {
+                    self.mapping = Self::guess_mapping(&self.workbook, &sheet, header_row - 1);
+                    mapping_changed = true;
+                }
+            });
+
+            ui.add_space(4.0);
+            self.render_preview(ui);
+            ui.separator();
+
+            mapping_changed |= self.render_mapping(ui);
+            ui.separator();
+
+            // Preview counts
+            match &self.staged {
+                Ok(staged) => {
+                    ui.label(format!(
+                        "Found {} courses and {} records",
+                        staged.course_definitions.len(),
+                        staged.course_records.len()
+                    ));
+
+                    if !staged.parse_report.rejected.is_empty() {
+                        ui.weak(staged.parse_report.to_string()).on_hover_ui(|ui| {
+                            staged
+                                .parse_report
+                                .rejected
+                                .iter()
+                                .take(20)
+                                .for_each(|row| {
+                                    ui.label(row.to_string());
+                                });
+                        });
+                    }
+                }
+                Err(err) => {
+                    ui.colored_label(ui.visuals().error_fg_color, err.to_string());
+                }
+            }
+
+            ui.add_space(8.0);
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                if ui
+                    .add_enabled(self.staged.is_ok(), egui::Button::new("Import"))
+                    .clicked()
+                {
+                    let staged = std::mem::replace(
+                        &mut self.staged,
+                        Err(ParseError::InvalidFormat("Already imported".to_owned())),
+                    );
+                    if let Ok(staged) = staged {
+                        app_ctx.replace_courses(staged, &self.file_name);
+                        result = SpreadsheetDialogResult::Imported;
+                    }
+                }
+
+                if ui.button("Cancel").clicked() {
+                    result = SpreadsheetDialogResult::Closed;
+                }
+            });
+        });
+
+        if mapping_changed {
+            self.staged = CourseManager::stage_spreadsheet(
+                &self.workbook,
+                &self.mapping,
+                &app_ctx.parse_options(),
+            );
+        }
+
+        if modal.should_close() && matches!(result, SpreadsheetDialogResult::Open) {
+            result = SpreadsheetDialogResult::Closed;
+        }
+
+        result
+    }
+}
+
+/// "B: Course Code" style labels
+fn column_title(headers: &[String], col: usize) -> String {
+    let letter = column_letter(col);
+    match headers.get(col).filter(|header| !header.is_empty()) {
+        Some(header) => format!("{}: {}", letter, header),
+        None => letter,
+    }
+}
+
+/// 0 -> A, 26 -> AA
+fn column_letter(mut col: usize) -> String {
+    let mut letters = Vec::new();
+    loop {
+        letters.push((b'A' + (col % 26) as u8) as char);
+        if col < 26 {
+            break;
+        }
+        col = col / 26 - 1;
+    }
+
+    letters.iter().rev().collect()
+}
@@ -1,9 +1,13 @@
 use super::TITLEBAR_HEIGHT;
-use crate::views::View;
+use crate::{CrynContext, views::View};
 
 const TITLEBAR_PADDING_H: f32 = 12.0;
 
-pub fn render_title_bar(ctx: &egui::Context, current_view: Option<&Box<dyn View>>) {
+pub fn render_title_bar(
+    ctx: &egui::Context,
+    app_ctx: &CrynContext,
+    current_view: Option<&dyn View>,
+) {
     egui::TopBottomPanel::top("titlebar")
         .frame(
             egui::Frame::new()
@@ -26,6 +30,13 @@ pub fn render_title_bar(ctx: &egui::Context, current_view: Option<&Box<dyn View>
                     ui.add_space(TITLEBAR_PADDING_H);
                     ui.label("Cryn - Ammar Magnus");
 
+                    // Loaded data source
+                    let source_name = app_ctx.course_manager.borrow().source_name.clone();
+                    if !source_name.is_empty() {
+                        ui.add_space(TITLEBAR_PADDING_H);
+                        ui.weak(source_name);
+                    }
+
                     // View name
                     if let Some(current_view) = current_view {
                         let title_width = ui
@@ -0,0 +1,133 @@
+mod common;
+
+use chrono::Weekday;
+use common::RecordBuilder;
+use cryn_rs::{
+    models::{CampusMap, SchedulePreferences},
+    services::{
+        CourseManager, GenerationJob, Schedule, ScheduleProblem, ScheduleSearch, TopSchedules,
+        rank_schedules, score_schedule,
+    },
+};
+use std::{sync::Arc, time::Duration};
+
+/// 4 courses with 3 lecture groups spread over 2 days, 2 hour periods
+fn problem() -> ScheduleProblem {
+    let mut course_manager = CourseManager::new();
+    for course_idx in 0..4 {
+        let code = format!("CSE{}", 111 + course_idx);
+        for group in 1..=3 {
+            let day = if group % 2 == 0 {
+                Weekday::Sun
+            } else {
+                Weekday::Mon
+            };
+
+            RecordBuilder::new(&code)
+                .group(group)
+                .day(day)
+                .period(8 + 2 * ((course_idx + group as u32) % 4))
+                .add_to(&mut course_manager);
+        }
+    }
+
+    ScheduleProblem::from_course_manager(&course_manager, Default::default(), &[])
+}
+
+#[test]
+fn search_in_chunks_matches_generate() {
+    let problem = problem();
+    let expected = problem.generate(1000);
+    assert!(!expected.is_empty());
+
+    let mut search = ScheduleSearch::new(&problem, 1000);
+    let mut schedules = Vec::new();
+    let mut last_progress = 0.0;
+    while !search.is_done() {
+        schedules.extend(search.advance(&problem, 2));
+
+        let progress = search.progress(&problem);
+        assert!(progress >= last_progress);
+        last_progress = progress;
+    }
+
+    assert_eq!(schedules, expected);
+    assert_eq!(search.found(), expected.len());
+    assert_eq!(last_progress, 1.0);
+}
+
+#[test]
+fn search_stops_at_the_cap() {
+    let problem = problem();
+    let mut search = ScheduleSearch::new(&problem, 2);
+
+    assert_eq!(search.advance(&problem, usize::MAX).len(), 2);
+    assert!(search.is_done());
+}
+
+fn start(problem: &Arc<ScheduleProblem>, max_schedules: usize) -> GenerationJob {
+    GenerationJob::start(
+        Arc::clone(problem),
+        SchedulePreferences::default(),
+        CampusMap::bundled(),
+        max_schedules,
+    )
+}
+
+/// Everything generated, ranked, cut to `max_schedules`
+fn best(problem: &ScheduleProblem, max_schedules: usize) -> Vec<Schedule> {
+    let mut schedules = problem.generate(usize::MAX);
+    rank_schedules(
+        problem,
+        &mut schedules,
+        &SchedulePreferences::default(),
+        &CampusMap::bundled(),
+    );
+    schedules.truncate(max_schedules);
+    schedules
+}
+
+#[test]
+fn top_schedules_keep_the_lowest_penalties() {
+    let problem = problem();
+    let preferences = SchedulePreferences::default();
+    let campus_map = CampusMap::bundled();
+
+    let mut top = TopSchedules::new(3);
+    for schedule in problem.generate(usize::MAX) {
+        let penalty = score_schedule(&problem, &schedule, &preferences, &campus_map).penalty;
+        top.push(penalty, schedule);
+    }
+
+    assert!(top.seen() > 3);
+    assert_eq!(top.ranked(), best(&problem, 3));
+}
+
+#[test]
+fn job_ranks_the_whole_search() {
+    let problem = Arc::new(problem());
+    let expected = best(&problem, 3);
+
+    // Best of everything, not of the first 3 found
+    let mut job = start(&problem, 3);
+    let mut schedules = Vec::new();
+    while !job.is_done() {
+        if let Some(ranked) = job.poll() {
+            assert!(ranked.len() <= 3);
+            schedules = ranked;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    assert_eq!(schedules, expected);
+    assert_eq!(job.found(), problem.generate(usize::MAX).len());
+}
+
+#[test]
+fn cancelled_job_stops_reporting() {
+    let mut job = start(&Arc::new(problem()), 1000);
+    job.cancel();
+
+    assert!(job.is_done());
+    assert!(job.poll().is_none());
+}
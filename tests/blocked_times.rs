@@ -0,0 +1,118 @@
+mod common;
+
+use chrono::Weekday;
+use common::{RecordBuilder, offered_groups, time};
+use cryn_rs::{
+    models::{BlockedTime, CampusMap, CourseRecordType, SchedulePreferences, TimeFormat},
+    services::{CourseManager, ScheduleProblem, find_blocked_conflicts, rank_schedules},
+};
+
+/// CSE111 lecture groups 1 (Sun), 2 (Mon) and 3 (Tue), all 10:00 - 11:50
+fn course_manager() -> CourseManager {
+    let mut course_manager = CourseManager::new();
+    for (group, day) in [(1, Weekday::Sun), (2, Weekday::Mon), (3, Weekday::Tue)] {
+        RecordBuilder::new("CSE111")
+            .group(group)
+            .day(day)
+            .period(10)
+            .add_to(&mut course_manager);
+    }
+
+    course_manager
+}
+
+fn sunday_morning(hard: bool) -> BlockedTime {
+    BlockedTime {
+        hard,
+        ..BlockedTime::new(Weekday::Sun, time(8, 0), time(11, 0))
+    }
+}
+
+#[test]
+fn overlap_is_clipped_to_the_block() {
+    let blocked_time = sunday_morning(true);
+
+    assert_eq!(
+        blocked_time.overlap_minutes(Weekday::Sun, time(10, 0), time(11, 50)),
+        60
+    );
+    assert_eq!(
+        blocked_time.overlap_minutes(Weekday::Sun, time(11, 0), time(12, 50)),
+        0
+    );
+    assert_eq!(
+        blocked_time.overlap_minutes(Weekday::Mon, time(10, 0), time(11, 50)),
+        0
+    );
+}
+
+#[test]
+fn hard_blocks_remove_groups_soft_ones_dont() {
+    let course_manager = course_manager();
+
+    assert_eq!(
+        offered_groups(&course_manager, Default::default(), &[]),
+        [1, 2, 3]
+    );
+    assert_eq!(
+        offered_groups(&course_manager, Default::default(), &[sunday_morning(true)]),
+        [2, 3]
+    );
+    assert_eq!(
+        offered_groups(
+            &course_manager,
+            Default::default(),
+            &[sunday_morning(false)]
+        ),
+        [1, 2, 3]
+    );
+}
+
+#[test]
+fn pinned_group_ignores_hard_blocks() {
+    let course_manager = course_manager();
+    course_manager.course_definitions[0]
+        .borrow_mut()
+        .pinned_groups
+        .insert(CourseRecordType::Lecture, 1);
+
+    assert_eq!(
+        offered_groups(&course_manager, Default::default(), &[sunday_morning(true)]),
+        [1]
+    );
+}
+
+#[test]
+fn soft_blocks_rank_schedules_lower() {
+    let course_manager = course_manager();
+    let problem = ScheduleProblem::from_course_manager(
+        &course_manager,
+        Default::default(),
+        &[sunday_morning(false)],
+    );
+
+    let mut schedules = problem.generate(10);
+    rank_schedules(
+        &problem,
+        &mut schedules,
+        &SchedulePreferences::default(),
+        &CampusMap::bundled(),
+    );
+
+    let last_groups = schedules.last().unwrap().saved_groups(&problem);
+    assert_eq!(last_groups[0].group, 1);
+}
+
+#[test]
+fn conflicts_list_records_during_busy_times() {
+    let course_manager = course_manager();
+    let blocked_times = [sunday_morning(false)];
+
+    let conflicts = find_blocked_conflicts(&course_manager.course_records, &blocked_times);
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].record.borrow().group, 1);
+    assert_eq!(
+        conflicts[0].describe(TimeFormat::TwelveHour),
+        "CSE111 Lecture G1 overlaps Busy (Sunday 8:00 AM - 11:00 AM, soft)"
+    );
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use chrono::{DateTime, NaiveDate, Utc, Weekday};
+use common::{RecordBuilder, time};
+use cryn_rs::{
+    models::{CourseDefinition, CourseRecord, CourseRecordType, Location, Semester},
+    services::export_icalendar,
+};
+use std::{cell::RefCell, rc::Rc};
+
+fn record(
+    definition: &Rc<RefCell<CourseDefinition>>,
+    record_type: CourseRecordType,
+    group: i32,
+    day: Weekday,
+    from: (u32, u32),
+    to: (u32, u32),
+    location: &str,
+) -> Rc<RefCell<CourseRecord>> {
+    RecordBuilder::new(&definition.borrow().code)
+        .group(group)
+        .record_type(record_type)
+        .day(day)
+        .times(time(from.0, from.1), time(to.0, to.1))
+        .seats(60, 42, 0)
+        .location(Location::parse(location))
+        .build(definition)
+}
+
+fn semester() -> Semester {
+    Semester {
+        start: NaiveDate::from_ymd_opt(2025, 2, 8).unwrap(),
+        end: NaiveDate::from_ymd_opt(2025, 5, 29).unwrap(),
+    }
+}
+
+fn timestamp() -> DateTime<Utc> {
+    "2025-01-20T10:00:00Z".parse().unwrap()
+}
+
+#[test]
+fn exports_weekly_events() {
+    let programming = Rc::new(RefCell::new(CourseDefinition::new("CSE111", "Programming")));
+    let physics = Rc::new(RefCell::new(CourseDefinition::new("PHY101", "Physics I")));
+
+    let records = vec![
+        record(
+            &physics,
+            CourseRecordType::Tutorial,
+            3,
+            Weekday::Mon,
+            (13, 30),
+            (15, 0),
+            "B2-101",
+        ),
+        record(
+            &programming,
+            CourseRecordType::Lecture,
+            1,
+            Weekday::Sat,
+            (9, 0),
+            (11, 0),
+            "Hall 5",
+        ),
+        record(
+            &programming,
+            CourseRecordType::Tutorial,
+            2,
+            Weekday::Wed,
+            (11, 15),
+            (12, 45),
+            "Lab 3",
+        ),
+    ];
+
+    insta::assert_snapshot!(export_icalendar(&records, &semester(), timestamp()));
+}
+
+#[test]
+fn escapes_and_folds_text() {
+    let definition = Rc::new(RefCell::new(CourseDefinition::new(
+        "ARC201",
+        "History, Theory; and Criticism of Architecture and Urban Design in the Modern Era",
+    )));
+
+    let records = vec![record(
+        &definition,
+        CourseRecordType::Lecture,
+        1,
+        Weekday::Sun,
+        (10, 0),
+        (12, 0),
+        "Building A; Room 2, Floor 1",
+    )];
+
+    insta::assert_snapshot!(export_icalendar(&records, &semester(), timestamp()));
+}
+
+#[test]
+fn skips_sessions_after_the_semester() {
+    let definition = Rc::new(RefCell::new(CourseDefinition::new("MTH101", "Calculus")));
+    let records = vec![record(
+        &definition,
+        CourseRecordType::Lecture,
+        1,
+        Weekday::Thu,
+        (8, 0),
+        (10, 0),
+        "Hall 1",
+    )];
+
+    // Saturday to Tuesday, no Thursday in between
+    let short_semester = Semester {
+        start: NaiveDate::from_ymd_opt(2025, 2, 8).unwrap(),
+        end: NaiveDate::from_ymd_opt(2025, 2, 11).unwrap(),
+    };
+
+    insta::assert_snapshot!(export_icalendar(&records, &short_semester, timestamp()));
+}
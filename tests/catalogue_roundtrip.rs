@@ -0,0 +1,121 @@
+use cryn_rs::{
+    models::CourseParseFormat,
+    services::{
+        CatalogueCsvParser, CatalogueJsonParser, CourseManager, ParseError, ParseInput,
+        ParseOptions, ParserRegistry, PortalParser, catalogue_to_csv, catalogue_to_json,
+    },
+};
+
+const SAMPLE_COURSES: &str = include_str!("../assets/data/sample_courses.txt");
+const FACULTY_TIMETABLE: &[u8] = include_bytes!("fixtures/faculty_timetable.xlsx");
+
+/// Every field of every record, ties in day and time can come back in any order
+fn describe(course_manager: &CourseManager) -> Vec<String> {
+    let mut rows: Vec<String> = course_manager
+        .course_records
+        .iter()
+        .map(|record| {
+            let record = record.borrow();
+            let def = record.course_definition.borrow();
+            format!(
+                "{:?}|{}|{}|{:?}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{:?}|{:?}",
+                record.id,
+                def.code,
+                def.name,
+                record.record_type,
+                record.group,
+                record.day,
+                record.start_time,
+                record.end_time,
+                record.class_size,
+                record.enrolled,
+                record.waiting,
+                record.status,
+                record.location,
+                def.flags,
+                record.snapshot_time,
+                record.parse_format
+            )
+        })
+        .collect();
+
+    rows.sort();
+    rows
+}
+
+fn stage(parser_id: &str, text: &str) -> Result<CourseManager, ParseError> {
+    CourseManager::stage_courses_as(
+        &ParserRegistry::default(),
+        parser_id,
+        &ParseInput::from_text("test", text),
+        &ParseOptions::default(),
+    )
+}
+
+fn load_sample() -> CourseManager {
+    stage(PortalParser::ID, SAMPLE_COURSES).unwrap()
+}
+
+#[test]
+fn json_round_trip_keeps_every_field() {
+    let original = load_sample();
+    let json = catalogue_to_json(&original).unwrap();
+    let reimported = stage(CatalogueJsonParser::ID, &json).unwrap();
+
+    assert_eq!(reimported.parse_report.skipped(), 0);
+    assert_eq!(describe(&original), describe(&reimported));
+}
+
+#[test]
+fn csv_round_trip_keeps_every_field() {
+    let original = load_sample();
+    let csv = catalogue_to_csv(&original).unwrap();
+    let reimported = stage(CatalogueCsvParser::ID, &csv).unwrap();
+
+    assert_eq!(reimported.parse_report.skipped(), 0);
+    assert_eq!(describe(&original), describe(&reimported));
+}
+
+#[test]
+fn round_trips_keep_the_parse_format() {
+    let original = CourseManager::stage_courses(
+        &ParserRegistry::default(),
+        &ParseInput::new("timetable.xlsx", FACULTY_TIMETABLE),
+        &ParseOptions::default(),
+    )
+    .unwrap();
+    assert!(
+        original
+            .course_records
+            .iter()
+            .all(|record| record.borrow().parse_format == CourseParseFormat::Excel)
+    );
+
+    let json = stage(
+        CatalogueJsonParser::ID,
+        &catalogue_to_json(&original).unwrap(),
+    )
+    .unwrap();
+    let csv = stage(
+        CatalogueCsvParser::ID,
+        &catalogue_to_csv(&original).unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(describe(&original), describe(&json));
+    assert_eq!(describe(&original), describe(&csv));
+}
+
+#[test]
+fn csv_rejects_bad_rows_only() {
+    let csv = "\
+code,name,type,group,day,from,to,class_size,enrolled,waiting,status,location
+CSE111,Programming,Lecture,1,Sat,09:00,11:00,60,42,0,Open,Hall 5
+CSE111,Programming,Lecture,2,Someday,09:00,11:00,60,42,0,Open,Hall 5
+CSE111,Programming,Tutorial,1,Mon,13:00,14:00,thirty,20,0,Open,Lab 3
+";
+    let staged = stage(CatalogueCsvParser::ID, csv).unwrap();
+
+    assert_eq!(staged.parse_report.imported, 1);
+    assert_eq!(staged.parse_report.skipped(), 2);
+}
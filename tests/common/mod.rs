@@ -0,0 +1,136 @@
+//! Fixtures shared by the integration tests and the benches
+//! Every test binary only uses some of them
+#![allow(dead_code)]
+
+use chrono::{NaiveTime, Weekday};
+use cryn_rs::{
+    models::{
+        BlockedTime, CourseDefinition, CourseParseFormat, CourseRecord, CourseRecordType,
+        GroupFilter, Location, RecordStatus,
+    },
+    services::{CourseManager, ScheduleProblem},
+};
+use std::{cell::RefCell, rc::Rc};
+
+pub fn time(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+/// Course record with fixture defaults, set only what the test cares about
+/// Starts as an open lecture G1 on Sunday 9:00 - 10:50, 30 seats and nobody enrolled
+pub struct RecordBuilder {
+    code: String,
+    group: i32,
+    record_type: CourseRecordType,
+    day: Weekday,
+    start_time: NaiveTime,
+    end_time: NaiveTime,
+    class_size: i32,
+    enrolled: i32,
+    waiting: i32,
+    status: RecordStatus,
+    location: Location,
+}
+
+impl RecordBuilder {
+    pub fn new(code: &str) -> Self {
+        Self {
+            code: code.to_owned(),
+            group: 1,
+            record_type: CourseRecordType::Lecture,
+            day: Weekday::Sun,
+            start_time: time(9, 0),
+            end_time: time(10, 50),
+            class_size: 30,
+            enrolled: 0,
+            waiting: 0,
+            status: RecordStatus::Open,
+            location: Location::default(),
+        }
+    }
+
+    pub fn group(mut self, group: i32) -> Self {
+        self.group = group;
+        self
+    }
+
+    pub fn record_type(mut self, record_type: CourseRecordType) -> Self {
+        self.record_type = record_type;
+        self
+    }
+
+    pub fn day(mut self, day: Weekday) -> Self {
+        self.day = day;
+        self
+    }
+
+    /// Usual 1h50 period starting on the hour
+    pub fn period(self, hour: u32) -> Self {
+        self.times(time(hour, 0), time(hour + 1, 50))
+    }
+
+    pub fn times(mut self, start_time: NaiveTime, end_time: NaiveTime) -> Self {
+        self.start_time = start_time;
+        self.end_time = end_time;
+        self
+    }
+
+    pub fn seats(mut self, class_size: i32, enrolled: i32, waiting: i32) -> Self {
+        self.class_size = class_size;
+        self.enrolled = enrolled;
+        self.waiting = waiting;
+        self
+    }
+
+    pub fn status(mut self, status: RecordStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn location(mut self, location: Location) -> Self {
+        self.location = location;
+        self
+    }
+
+    /// Record of an existing course, `code` is ignored then
+    pub fn build(self, definition: &Rc<RefCell<CourseDefinition>>) -> Rc<RefCell<CourseRecord>> {
+        Rc::new(RefCell::new(CourseRecord::new(
+            Rc::clone(definition),
+            self.group,
+            self.record_type,
+            self.day,
+            self.start_time,
+            self.end_time,
+            self.class_size,
+            self.enrolled,
+            self.waiting,
+            self.status,
+            self.location,
+            CourseParseFormat::Standard,
+        )))
+    }
+
+    /// Adds the record, its course gets added and selected on first use
+    pub fn add_to(self, course_manager: &mut CourseManager) -> Rc<RefCell<CourseRecord>> {
+        let definition = course_manager.get_or_add_course_definition(&self.code, &self.code);
+        definition.borrow_mut().selected = true;
+
+        let record = self.build(&definition);
+        course_manager.course_records.push(Rc::clone(&record));
+        record
+    }
+}
+
+/// Groups the generator still offers for the first slot
+pub fn offered_groups(
+    course_manager: &CourseManager,
+    group_filter: GroupFilter,
+    blocked_times: &[BlockedTime],
+) -> Vec<i32> {
+    let problem = ScheduleProblem::from_course_manager(course_manager, group_filter, blocked_times);
+    problem.slots[0]
+        .bundles
+        .iter()
+        .map(|bundle| bundle.group)
+        .collect()
+}
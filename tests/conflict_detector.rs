@@ -0,0 +1,84 @@
+mod common;
+
+use chrono::Weekday;
+use common::{RecordBuilder, time};
+use cryn_rs::{
+    models::{CourseRecordType, TimeFormat},
+    services::{CourseManager, find_conflicts, records_conflict},
+};
+use std::rc::Rc;
+
+#[test]
+fn overlapping_records_conflict() {
+    let mut course_manager = CourseManager::new();
+    let lecture = RecordBuilder::new("CSE111").add_to(&mut course_manager);
+    let overlapping = RecordBuilder::new("MTH101")
+        .times(time(10, 0), time(11, 50))
+        .add_to(&mut course_manager);
+    let touching = RecordBuilder::new("PHY101")
+        .times(time(10, 50), time(12, 0))
+        .add_to(&mut course_manager);
+    let other_day = RecordBuilder::new("ENG101")
+        .day(Weekday::Mon)
+        .add_to(&mut course_manager);
+
+    assert!(records_conflict(&lecture.borrow(), &overlapping.borrow()));
+    assert!(!records_conflict(&lecture.borrow(), &touching.borrow()));
+    assert!(!records_conflict(&lecture.borrow(), &other_day.borrow()));
+}
+
+#[test]
+fn alternative_groups_never_conflict() {
+    let mut course_manager = CourseManager::new();
+    let group_1 = RecordBuilder::new("CSE111").add_to(&mut course_manager);
+    let group_2 = RecordBuilder::new("CSE111")
+        .group(2)
+        .add_to(&mut course_manager);
+    let tutorial = RecordBuilder::new("CSE111")
+        .record_type(CourseRecordType::Tutorial)
+        .add_to(&mut course_manager);
+
+    // Only one lecture group gets picked, but its tutorial is attended too
+    assert!(!records_conflict(&group_1.borrow(), &group_2.borrow()));
+    assert!(records_conflict(&group_1.borrow(), &tutorial.borrow()));
+}
+
+#[test]
+fn find_conflicts_lists_every_pair_once() {
+    let mut course_manager = CourseManager::new();
+    let late = RecordBuilder::new("PHY101")
+        .times(time(10, 0), time(11, 0))
+        .add_to(&mut course_manager);
+    let early = RecordBuilder::new("CSE111")
+        .times(time(8, 0), time(12, 0))
+        .add_to(&mut course_manager);
+    let middle = RecordBuilder::new("MTH101")
+        .times(time(9, 0), time(10, 30))
+        .add_to(&mut course_manager);
+    RecordBuilder::new("ENG101")
+        .times(time(12, 0), time(13, 0))
+        .add_to(&mut course_manager);
+
+    let conflicts = find_conflicts(&course_manager.course_records);
+    assert_eq!(conflicts.len(), 3);
+
+    // Earliest start first on either side
+    assert!(Rc::ptr_eq(&conflicts[0].first, &early));
+    assert!(Rc::ptr_eq(&conflicts[0].second, &middle));
+    assert!(
+        conflicts
+            .iter()
+            .all(|conflict| !conflict.involves(&course_manager.course_records[3]))
+    );
+
+    let with_late: Vec<_> = conflicts
+        .iter()
+        .filter_map(|conflict| conflict.other(&late))
+        .collect();
+    assert_eq!(with_late.len(), 2);
+
+    assert_eq!(
+        conflicts[0].describe(TimeFormat::TwentyFourHour),
+        "CSE111 Lecture G1 clashes with MTH101 Lecture G1 on Sunday (09:00 - 10:30)"
+    );
+}
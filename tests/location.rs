@@ -0,0 +1,100 @@
+use cryn_rs::{
+    models::Location,
+    services::{CourseManager, ParseInput, ParseOptions, ParserRegistry, PortalParser},
+};
+
+const SAMPLE_COURSES: &str = include_str!("../assets/data/sample_courses.txt");
+
+/// room_code|room|building|campus|capacity, blank for None
+fn describe(location: &Location) -> String {
+    [
+        location.room_code.clone().unwrap_or_default(),
+        location.room.clone(),
+        location.building.clone().unwrap_or_default(),
+        location.campus.clone().unwrap_or_default(),
+        location
+            .capacity
+            .map(|capacity| capacity.to_string())
+            .unwrap_or_default(),
+    ]
+    .join("|")
+}
+
+#[test]
+fn portal_locations_are_split_up() {
+    let cases = [
+        (
+            "[20103]20103-60-الجيزة الرئيسي",
+            "20103|20103|20|الجيزة الرئيسي|60",
+        ),
+        (
+            "[204071]20407 A -60-الجيزة الرئيسي",
+            "204071|20407 A|20|الجيزة الرئيسي|60",
+        ),
+        (
+            "[9301] ELC. Bulding               -45-الجيزة الرئيسي",
+            "9301|ELC. Bulding|9|الجيزة الرئيسي|45",
+        ),
+        // No capacity, or a room kind where it should be
+        ("[51216]51216-الشيخ زايد", "51216|51216|51|الشيخ زايد|"),
+        (
+            "[51219]51219-صالة رسم-الشيخ زايد",
+            "51219|51219-صالة رسم|51|الشيخ زايد|",
+        ),
+        // Unassigned room id
+        ("[0]17101-0-الجيزة الرئيسي", "0|17101|17|الجيزة الرئيسي|0"),
+    ];
+
+    for (raw, expected) in cases {
+        let location = Location::parse(raw);
+        assert_eq!(describe(&location), expected, "{}", raw);
+        assert_eq!(location.raw, raw);
+    }
+}
+
+#[test]
+fn other_sources_keep_the_room_name() {
+    let location = Location::parse(" Hall-5 ");
+    assert_eq!(describe(&location), "|Hall-5|||");
+    assert_eq!(location.room_label(), "Hall-5");
+
+    assert_eq!(Location::parse("20103").building.as_deref(), Some("20"));
+    assert!(Location::parse("").is_empty());
+    assert!(Location::parse("-----").is_empty());
+}
+
+#[test]
+fn labels_are_readable() {
+    let location = Location::parse("[20103]20103-60-الجيزة الرئيسي");
+    assert_eq!(location.room_label(), "Room 20103");
+    assert_eq!(location.to_string(), "Room 20103, الجيزة الرئيسي");
+
+    let location = Location::parse("[1520]Blue Room-45-الجيزة الرئيسي");
+    assert_eq!(location.room_label(), "Blue Room");
+    assert_eq!(location.building.as_deref(), Some("1"));
+}
+
+#[test]
+fn sample_courses_group_by_building() {
+    let staged = CourseManager::stage_courses_as(
+        &ParserRegistry::default(),
+        PortalParser::ID,
+        &ParseInput::from_text("test", SAMPLE_COURSES),
+        &ParseOptions::default(),
+    )
+    .unwrap();
+
+    assert!(staged.course_records.iter().all(|record| {
+        let location = &record.borrow().location;
+        location.is_empty() || (location.building.is_some() && location.campus.is_some())
+    }));
+
+    // Numeric order, not text order
+    let buildings: Vec<String> = staged
+        .courses_by_building()
+        .into_iter()
+        .map(|(building, _)| building)
+        .collect();
+    assert_eq!(buildings.first().map(String::as_str), Some("1"));
+    assert!(buildings.iter().position(|b| b == "9") < buildings.iter().position(|b| b == "14"));
+}
@@ -0,0 +1,140 @@
+use cryn_rs::services::{
+    CatalogueCsvParser, CatalogueJsonParser, CourseManager, CourseParser, DuplicateParserId,
+    ParseError, ParseInput, ParseOptions, ParseReport, ParserRegistry, PortalParser,
+    SpreadsheetParser, catalogue_to_csv, catalogue_to_json,
+};
+
+const SAMPLE_COURSES: &str = include_str!("../assets/data/sample_courses.txt");
+const FACULTY_TIMETABLE: &[u8] = include_bytes!("fixtures/faculty_timetable.xlsx");
+
+fn detected_parser(input: &ParseInput) -> Option<&'static str> {
+    ParserRegistry::default()
+        .detect(input)
+        .map(|parser| parser.id())
+}
+
+fn sample_manager() -> CourseManager {
+    CourseManager::stage_courses(
+        &ParserRegistry::default(),
+        &ParseInput::from_text("Pasted data", SAMPLE_COURSES),
+        &ParseOptions::default(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn detects_builtin_formats() {
+    let sample = sample_manager();
+    let json = catalogue_to_json(&sample).unwrap();
+    let csv = catalogue_to_csv(&sample).unwrap();
+
+    assert_eq!(
+        detected_parser(&ParseInput::from_text("Pasted data", SAMPLE_COURSES)),
+        Some(PortalParser::ID)
+    );
+    // Content wins over a misleading name
+    assert_eq!(
+        detected_parser(&ParseInput::from_text("courses.txt", &json)),
+        Some(CatalogueJsonParser::ID)
+    );
+    assert_eq!(
+        detected_parser(&ParseInput::from_text("courses", &csv)),
+        Some(CatalogueCsvParser::ID)
+    );
+    assert_eq!(
+        detected_parser(&ParseInput::new("timetable.bin", FACULTY_TIMETABLE)),
+        Some(SpreadsheetParser::ID)
+    );
+}
+
+#[test]
+fn unknown_input_is_rejected() {
+    let input = ParseInput::from_text("notes.md", "# nothing to see here");
+
+    assert_eq!(detected_parser(&input), None);
+    assert!(matches!(
+        CourseManager::stage_courses(&ParserRegistry::default(), &input, &ParseOptions::default()),
+        Err(ParseError::UnknownFormat)
+    ));
+}
+
+/// Claims anything mentioning "faculty", parses it as portal data
+struct FacultyParser;
+
+impl CourseParser for FacultyParser {
+    fn id(&self) -> &'static str {
+        "faculty"
+    }
+
+    fn detect(&self, input: &ParseInput) -> f32 {
+        if input.text().contains("faculty") {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    fn parse(
+        &self,
+        staging: &mut CourseManager,
+        _input: &ParseInput,
+        options: &ParseOptions,
+    ) -> Result<ParseReport, ParseError> {
+        PortalParser.parse(
+            staging,
+            &ParseInput::from_text("faculty", SAMPLE_COURSES),
+            options,
+        )
+    }
+}
+
+#[test]
+fn registered_parsers_take_part_in_detection() {
+    let mut registry = ParserRegistry::new();
+    registry.register(FacultyParser).unwrap();
+
+    let staged = CourseManager::stage_courses(
+        &registry,
+        &ParseInput::from_text("export.faculty", "faculty export"),
+        &ParseOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        staged.course_records.len(),
+        sample_manager().course_records.len()
+    );
+}
+
+#[test]
+fn loaded_data_remembers_its_parser() {
+    let mut registry = ParserRegistry::default();
+    registry.register(FacultyParser).unwrap();
+
+    let staged = CourseManager::stage_courses(
+        &registry,
+        &ParseInput::from_text("export.faculty", "faculty export"),
+        &ParseOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(staged.parser_id, "faculty");
+
+    // Re-parsing goes through the same parser, not the builtin portal one
+    let restaged = staged.restage(&registry, &ParseOptions::default()).unwrap();
+    assert_eq!(restaged.parser_id, "faculty");
+}
+
+#[test]
+fn duplicate_ids_are_rejected() {
+    let mut registry = ParserRegistry::new();
+    registry.register(FacultyParser).unwrap();
+
+    assert_eq!(
+        registry.register(FacultyParser),
+        Err(DuplicateParserId("faculty"))
+    );
+    assert_eq!(
+        ParserRegistry::default().register(PortalParser),
+        Err(DuplicateParserId(PortalParser::ID))
+    );
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use chrono::Weekday;
+use common::RecordBuilder;
+use cryn_rs::{
+    models::{CourseRecordType, GroupFilter, RecordStatus},
+    services::{CourseManager, ScheduleProblem, apply_selection, capture_selection},
+};
+
+/// CSE111 lectures 1-2 and tutorials 1-3, MTH101 lectures 1-2, all at 13:00
+/// CSE111 lecture 1 clashes with MTH101 lecture 2, CSE111 lecture 2 is closed
+fn course_manager() -> CourseManager {
+    let mut course_manager = CourseManager::new();
+    let rows = [
+        ("CSE111", CourseRecordType::Lecture, 1, Weekday::Sun),
+        ("CSE111", CourseRecordType::Lecture, 2, Weekday::Mon),
+        ("CSE111", CourseRecordType::Tutorial, 1, Weekday::Tue),
+        ("CSE111", CourseRecordType::Tutorial, 2, Weekday::Wed),
+        ("CSE111", CourseRecordType::Tutorial, 3, Weekday::Thu),
+        ("MTH101", CourseRecordType::Lecture, 1, Weekday::Sat),
+        ("MTH101", CourseRecordType::Lecture, 2, Weekday::Sun),
+    ];
+
+    for (code, record_type, group, day) in rows {
+        let status = if code == "CSE111" && record_type == CourseRecordType::Lecture && group == 2 {
+            RecordStatus::Closed
+        } else {
+            RecordStatus::Open
+        };
+
+        RecordBuilder::new(code)
+            .group(group)
+            .record_type(record_type)
+            .day(day)
+            .period(13)
+            .status(status)
+            .add_to(&mut course_manager);
+    }
+
+    course_manager
+}
+
+fn pin(course_manager: &CourseManager, code: &str, record_type: CourseRecordType, group: i32) {
+    course_manager
+        .course_definitions
+        .iter()
+        .find(|def| def.borrow().code == code)
+        .unwrap()
+        .borrow_mut()
+        .pinned_groups
+        .insert(record_type, group);
+}
+
+/// Picked (code, type, group) per schedule
+fn generate(course_manager: &CourseManager, group_filter: GroupFilter) -> Vec<Vec<String>> {
+    let problem = ScheduleProblem::from_course_manager(course_manager, group_filter, &[]);
+    problem
+        .generate(100)
+        .iter()
+        .map(|schedule| {
+            schedule
+                .saved_groups(&problem)
+                .iter()
+                .map(|group| format!("{} {:?} {}", group.code, group.record_type, group.group))
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn pinned_groups_are_kept_in_every_schedule() {
+    let course_manager = course_manager();
+    assert_eq!(generate(&course_manager, GroupFilter::default()).len(), 9);
+
+    pin(&course_manager, "CSE111", CourseRecordType::Tutorial, 3);
+    let schedules = generate(&course_manager, GroupFilter::default());
+
+    // Only the other slots vary
+    assert_eq!(schedules.len(), 3);
+    assert!(
+        schedules
+            .iter()
+            .all(|schedule| schedule.contains(&"CSE111 Tutorial 3".to_owned()))
+    );
+}
+
+#[test]
+fn pins_win_over_the_group_filter() {
+    let course_manager = course_manager();
+    pin(&course_manager, "CSE111", CourseRecordType::Lecture, 2);
+
+    let schedules = generate(
+        &course_manager,
+        GroupFilter {
+            skip_closed: true,
+            skip_full: true,
+        },
+    );
+
+    assert_eq!(schedules.len(), 6);
+    assert!(
+        schedules
+            .iter()
+            .all(|schedule| schedule.contains(&"CSE111 Lecture 2".to_owned()))
+    );
+}
+
+#[test]
+fn pins_limit_the_shown_groups() {
+    let course_manager = course_manager();
+    pin(&course_manager, "MTH101", CourseRecordType::Lecture, 1);
+
+    let groups: Vec<(String, i32)> = course_manager
+        .get_available_course_records()
+        .iter()
+        .map(|record| record.borrow())
+        .filter(|record| record.record_type == CourseRecordType::Lecture)
+        .map(|record| (record.course_definition.borrow().code.clone(), record.group))
+        .collect();
+
+    assert_eq!(
+        groups,
+        [
+            ("CSE111".to_owned(), 1),
+            ("CSE111".to_owned(), 2),
+            ("MTH101".to_owned(), 1)
+        ]
+    );
+}
+
+#[test]
+fn pins_are_saved_with_the_selection() {
+    let course_manager = course_manager();
+    pin(&course_manager, "CSE111", CourseRecordType::Tutorial, 2);
+    let selection = capture_selection(&course_manager, &[]);
+
+    let restored = self::course_manager();
+    apply_selection(&restored, &selection);
+
+    let definition = restored.course_definitions[0].borrow();
+    assert_eq!(definition.code, "CSE111");
+    assert_eq!(
+        definition.pinned_groups.get(&CourseRecordType::Tutorial),
+        Some(&2)
+    );
+    assert!(
+        restored.course_definitions[1]
+            .borrow()
+            .pinned_groups
+            .is_empty()
+    );
+}
@@ -0,0 +1,217 @@
+use chrono::NaiveDate;
+use cryn_rs::{
+    models::{CourseParseFormat, RecordKey},
+    services::{
+        CourseColumn, CourseManager, ParseError, ParseInput, ParseOptions, ParserRegistry,
+        PortalParser, read_html_tables,
+    },
+};
+
+const SAMPLE_COURSES: &str = include_str!("../assets/data/sample_courses.txt");
+
+fn stage_portal(text: &str) -> CourseManager {
+    CourseManager::stage_courses_as(
+        &ParserRegistry::default(),
+        PortalParser::ID,
+        &ParseInput::from_text("test", text),
+        &ParseOptions::default(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn sample_parses_every_row() {
+    let staged = stage_portal(SAMPLE_COURSES);
+
+    assert_eq!(staged.parse_report.imported, 763);
+    assert_eq!(staged.parse_report.skipped(), 0);
+}
+
+#[test]
+fn keeps_portal_ids_and_snapshot_time() {
+    let staged = stage_portal(SAMPLE_COURSES);
+    let snapshot_time = NaiveDate::from_ymd_opt(2026, 1, 21)
+        .unwrap()
+        .and_hms_opt(17, 30, 0);
+
+    assert_eq!(staged.snapshot_time(), snapshot_time);
+
+    let record = staged.find_record(&RecordKey::Id(3)).unwrap();
+    let record = record.borrow();
+    assert_eq!(record.course_definition.borrow().code, "GENS208");
+    assert_eq!(record.group, 1);
+    assert_eq!(record.snapshot_time, snapshot_time);
+}
+
+#[test]
+fn columns_are_found_by_header() {
+    let html = r#"
+        <table class="grid">
+            <tr><th scope="col">Location</th><th>Day</th><th>Code</th><th>Extra</th>
+                <th>Group</th><th>Name</th><th>Type</th><th>From</th><th>To</th></tr>
+            <tr>
+                <td class="loc">Hall&nbsp;A</td>
+                <td>Monday</td>
+                <td align="left">__CSE111__</td>
+                <td>ignored</td>
+                <td>2</td>
+                <td>Data &amp; Algorithms &#8211; &eacute;l&eacute;ments</td>
+                <td>Tutorial_</td>
+                <td>1:00_</td>
+                <td>2:50</td>
+            </tr>
+        </table>"#;
+
+    let staged = stage_portal(html);
+    let record = staged.course_records[0].borrow();
+    let definition = record.course_definition.borrow();
+
+    assert_eq!(definition.code, "CSE111");
+    assert_eq!(definition.name, "Data & Algorithms \u{2013} éléments");
+    assert_eq!(record.group, 2);
+    assert_eq!(record.location.room, "Hall A");
+    assert_eq!(record.start_time.to_string(), "13:00:00");
+    assert_eq!(record.class_size, 0);
+}
+
+#[test]
+fn rows_without_headers_use_the_portal_order() {
+    let html = "<tr><td>7</td><td>__MTH101__</td><td>Calculus</td><td>1</td><td>Lecture_</td>\
+        <td>Sunday</td><td>9:00_</td><td>10:50</td><td>30</td><td>10</td><td>0</td>\
+        <td>Opened</td><td>B12</td></tr>";
+
+    let staged = stage_portal(html);
+
+    assert_eq!(staged.parse_report.imported, 1);
+    assert_eq!(staged.course_records[0].borrow().enrolled, 10);
+}
+
+#[test]
+fn tables_tolerate_sloppy_markup() {
+    let tables =
+        read_html_tables("<TABLE><tr><th>A<th>B<!-- <td>no</td> --><tr><td>1<br>2<td>x</table>");
+
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0].headers, ["A", "B"]);
+    assert_eq!(tables[0].rows, [["1 2", "x"]]);
+}
+
+#[test]
+fn quoted_attributes_can_hold_angle_brackets() {
+    let tables = read_html_tables(
+        "<table><tr><th title=\"a > b\">A</th><th data-range='1>2'>B</th></tr>\
+         <tr><td class=it's>1</td><td title=\"broken>2</td></tr></table>",
+    );
+
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0].headers, ["A", "B"]);
+    assert_eq!(tables[0].rows, [["1", "2"]]);
+}
+
+/// Single portal row, the code and name cells as given
+fn stage_group(code: &str, name: &str, group: &str) -> Result<CourseManager, ParseError> {
+    let html = format!(
+        "<tr><td>1</td><td>__{code}__</td><td>{name}</td><td>{group}</td><td>Lecture</td>\
+         <td>Sunday</td><td>9:00</td><td>10:50</td><td>30</td><td>0</td><td>0</td>\
+         <td>Opened</td><td>B12</td></tr>"
+    );
+
+    CourseManager::stage_courses_as(
+        &ParserRegistry::default(),
+        PortalParser::ID,
+        &ParseInput::from_text("test", &html),
+        &ParseOptions::default(),
+    )
+}
+
+/// Code, name, group and format of the only record
+fn irregular_group(
+    code: &str,
+    name: &str,
+    group: &str,
+) -> (String, String, i32, CourseParseFormat) {
+    let staged = stage_group(code, name, group).unwrap();
+    let record = staged.course_records[0].borrow();
+    let definition = record.course_definition.borrow();
+
+    (
+        definition.code.clone(),
+        definition.name.clone(),
+        record.group,
+        record.parse_format,
+    )
+}
+
+#[test]
+fn irregular_groups_carry_the_real_course() {
+    assert_eq!(
+        irregular_group(
+            "LECS000",
+            "Lectures",
+            "MDPS478-Vehicle System Dynamics and Control- 3"
+        ),
+        (
+            "MDPS478".to_owned(),
+            "Vehicle System Dynamics and Control".to_owned(),
+            3,
+            CourseParseFormat::IrregularWithName
+        )
+    );
+    assert_eq!(
+        irregular_group("LECS000", "Lectures", "5-MTHS003"),
+        (
+            "MTHS003".to_owned(),
+            "Lectures".to_owned(),
+            5,
+            CourseParseFormat::IrregularWithoutName
+        )
+    );
+    assert_eq!(
+        irregular_group("LECS000", "Lectures", "5-5MTHS003"),
+        (
+            "MTHS003".to_owned(),
+            "Lectures".to_owned(),
+            5,
+            CourseParseFormat::IrregularWithoutNameGroupPrefixed
+        )
+    );
+    assert_eq!(
+        irregular_group("INTS203", "Internship", "INTS203-G.1"),
+        (
+            "INTS203".to_owned(),
+            "Internship".to_owned(),
+            1,
+            CourseParseFormat::IrregularWithoutNameGroupPostFixed
+        )
+    );
+    assert_eq!(
+        irregular_group("INTS203", "Internship", "INTS203-Seminar"),
+        (
+            "INTS203".to_owned(),
+            "Seminar".to_owned(),
+            -1,
+            CourseParseFormat::IrregularWithNameNoGroup
+        )
+    );
+}
+
+#[test]
+fn broken_irregular_groups_reject_the_row() {
+    for group in ["5-MTH", "123456789-", "MDPS478-Vehicle Dynamics- x"] {
+        let Err(ParseError::NoRecords(report)) = stage_group("LECS000", "Lectures", group) else {
+            panic!("{} should be rejected", group);
+        };
+
+        assert_eq!(report.rejected[0].column, CourseColumn::Group);
+    }
+}
+
+#[test]
+fn empty_codes_are_blamed_on_the_code_column() {
+    let Err(ParseError::NoRecords(report)) = stage_group("", "Programming", "1") else {
+        panic!("a row without a code should be rejected");
+    };
+
+    assert_eq!(report.rejected[0].column, CourseColumn::Code);
+    assert_eq!(report.rejected[0].reason, "Missing course code");
+}
@@ -0,0 +1,41 @@
+use cryn_rs::services::normalize_portal_text;
+
+#[test]
+fn plain_html_is_only_trimmed() {
+    assert_eq!(
+        normalize_portal_text("  <table><tr><td>A\\B</td></tr></table>\n"),
+        "<table><tr><td>A\\B</td></tr></table>"
+    );
+}
+
+#[test]
+fn script_response_gets_unescaped() {
+    assert_eq!(
+        normalize_portal_text(r#"<table class=\"grid\"></table>"#),
+        r#"<table class="grid"></table>"#
+    );
+}
+
+#[test]
+fn copies_escaped_twice_get_unescaped_twice() {
+    assert_eq!(
+        normalize_portal_text(r#"\\u003ctable class=\\\"grid\\\"\\u003e"#),
+        r#"<table class="grid">"#
+    );
+}
+
+#[test]
+fn whitespace_escapes_become_spaces() {
+    assert_eq!(
+        normalize_portal_text(r#"<td class=\"x\">A\nB\tC</td>"#),
+        r#"<td class="x">A B C</td>"#
+    );
+}
+
+#[test]
+fn unknown_escapes_are_kept() {
+    assert_eq!(
+        normalize_portal_text(r#"<td class=\"x\">\q \u12</td>"#),
+        r#"<td class="x">\q \u12</td>"#
+    );
+}
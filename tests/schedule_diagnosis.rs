@@ -0,0 +1,169 @@
+mod common;
+
+use chrono::Weekday;
+use common::{RecordBuilder, time};
+use cryn_rs::{
+    models::{
+        Availability, BlockedTime, CampusMap, CourseRecordType, GroupFilter, RecordStatus,
+        SchedulePreferences, TimeFormat,
+    },
+    services::{
+        CourseManager, GenerationJob, LeftOutReason, ScheduleDiagnoser, ScheduleProblem,
+        diagnose_schedules,
+    },
+};
+use std::{sync::Arc, time::Duration};
+
+/// CSE111 and MTH101 both only meet Sunday at 9, PHY101 has two Monday groups
+/// PHY101 group 2 is closed
+fn course_manager() -> CourseManager {
+    let mut course_manager = CourseManager::new();
+    let rows = [
+        ("CSE111", 1, Weekday::Sun, RecordStatus::Open),
+        ("MTH101", 1, Weekday::Sun, RecordStatus::Open),
+        ("PHY101", 1, Weekday::Mon, RecordStatus::Open),
+        ("PHY101", 2, Weekday::Mon, RecordStatus::Closed),
+    ];
+
+    for (code, group, day, status) in rows {
+        RecordBuilder::new(code)
+            .group(group)
+            .day(day)
+            .times(time(9, 0), time(11, 0))
+            .status(status)
+            .add_to(&mut course_manager);
+    }
+
+    course_manager
+}
+
+#[test]
+fn smallest_clashing_courses_and_suggestions() {
+    let course_manager = course_manager();
+    let problem = ScheduleProblem::from_course_manager(&course_manager, Default::default(), &[]);
+    assert!(problem.generate(10).is_empty());
+
+    let diagnosis = diagnose_schedules(&problem, 10);
+    assert!(diagnosis.empty_slots.is_empty());
+    assert_eq!(diagnosis.conflicting_courses, ["CSE111", "MTH101"]);
+
+    let clashes = diagnosis.clashes(&problem, &course_manager);
+    assert_eq!(clashes.len(), 1);
+    assert!(
+        clashes[0]
+            .describe(TimeFormat::TwentyFourHour)
+            .starts_with("CSE111 Lecture G1 clashes with MTH101 Lecture G1")
+    );
+
+    // Either way PHY101 keeps both of its groups
+    let suggestions: Vec<_> = diagnosis
+        .suggestions
+        .iter()
+        .map(|suggestion| (suggestion.course_code.as_str(), suggestion.schedule_count))
+        .collect();
+    assert_eq!(suggestions, [("CSE111", 2), ("MTH101", 2)]);
+}
+
+#[test]
+fn diagnosis_resumes_in_small_chunks() {
+    let course_manager = course_manager();
+    let problem = ScheduleProblem::from_course_manager(&course_manager, Default::default(), &[]);
+    let expected = diagnose_schedules(&problem, 10);
+
+    let mut diagnoser = ScheduleDiagnoser::new(&problem, 10);
+    let diagnosis = loop {
+        if let Some(diagnosis) = diagnoser.advance(&problem, 1) {
+            break diagnosis;
+        }
+    };
+
+    assert!(diagnoser.is_done());
+    assert_eq!(diagnosis.conflicting_courses, expected.conflicting_courses);
+    assert_eq!(diagnosis.suggestions, expected.suggestions);
+}
+
+#[test]
+fn job_delivers_the_diagnosis_when_done() {
+    let course_manager = course_manager();
+    let problem = Arc::new(ScheduleProblem::from_course_manager(
+        &course_manager,
+        Default::default(),
+        &[],
+    ));
+
+    let mut job = GenerationJob::start(
+        Arc::clone(&problem),
+        SchedulePreferences::default(),
+        CampusMap::bundled(),
+        10,
+    );
+    while !job.is_done() {
+        assert!(job.poll().is_none());
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    let diagnosis = job.take_diagnosis().expect("nothing found gets diagnosed");
+    assert_eq!(diagnosis.conflicting_courses, ["CSE111", "MTH101"]);
+}
+
+#[test]
+fn emptied_slots_say_why() {
+    let course_manager = course_manager();
+    course_manager.course_definitions[0].borrow_mut().selected = false;
+
+    // PHY101 G1 is blocked, G2 is closed
+    let group_filter = GroupFilter {
+        skip_closed: true,
+        skip_full: false,
+    };
+    let blocked_times = [BlockedTime::new(Weekday::Mon, time(10, 0), time(12, 0))];
+    let problem =
+        ScheduleProblem::from_course_manager(&course_manager, group_filter, &blocked_times);
+
+    let diagnosis = diagnose_schedules(&problem, 10);
+    assert_eq!(diagnosis.conflicting_courses, ["PHY101"]);
+    assert!(
+        diagnosis
+            .suggestions
+            .iter()
+            .all(|s| s.course_code == "PHY101")
+    );
+
+    let [slot] = diagnosis.empty_slots.as_slice() else {
+        panic!("expected one empty slot");
+    };
+    assert_eq!(slot.course_code, "PHY101");
+
+    let reasons: Vec<_> = slot
+        .left_out
+        .iter()
+        .map(|left_out| (left_out.group, left_out.reason.clone()))
+        .collect();
+    assert_eq!(
+        reasons,
+        [
+            (1, LeftOutReason::Blocked(blocked_times[0].clone())),
+            (2, LeftOutReason::Filtered(Availability::Closed)),
+        ]
+    );
+}
+
+#[test]
+fn pins_leave_out_the_other_groups() {
+    let course_manager = course_manager();
+    course_manager.course_definitions[2]
+        .borrow_mut()
+        .pinned_groups
+        .insert(CourseRecordType::Lecture, 2);
+
+    let problem = ScheduleProblem::from_course_manager(&course_manager, Default::default(), &[]);
+    let slot = problem
+        .slots
+        .iter()
+        .find(|slot| slot.course_code == "PHY101")
+        .unwrap();
+
+    assert_eq!(slot.left_out.len(), 1);
+    assert_eq!(slot.left_out[0].group, 1);
+    assert_eq!(slot.left_out[0].reason, LeftOutReason::NotPinned(2));
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use chrono::Weekday;
+use common::RecordBuilder;
+use cryn_rs::{
+    models::{CourseRecordType, SavedGroup},
+    services::{CourseManager, ScheduleProblem},
+};
+use std::rc::Rc;
+
+/// CSE111 lecture G1 meets twice, its Tuesday session clashes with the only MTH101 lecture
+/// Leaves CSE111 lecture G2 with either tutorial, 2 schedules
+fn catalogue() -> CourseManager {
+    let mut course_manager = CourseManager::new();
+
+    for day in [Weekday::Sun, Weekday::Tue] {
+        RecordBuilder::new("CSE111")
+            .day(day)
+            .period(9)
+            .add_to(&mut course_manager);
+    }
+    RecordBuilder::new("CSE111")
+        .group(2)
+        .day(Weekday::Mon)
+        .period(9)
+        .add_to(&mut course_manager);
+    for (group, day) in [(1, Weekday::Sun), (2, Weekday::Wed)] {
+        RecordBuilder::new("CSE111")
+            .group(group)
+            .record_type(CourseRecordType::Tutorial)
+            .day(day)
+            .period(11)
+            .add_to(&mut course_manager);
+    }
+
+    RecordBuilder::new("MTH101")
+        .day(Weekday::Tue)
+        .period(9)
+        .add_to(&mut course_manager);
+    RecordBuilder::new("MTH101")
+        .record_type(CourseRecordType::Tutorial)
+        .day(Weekday::Thu)
+        .period(13)
+        .add_to(&mut course_manager);
+
+    course_manager
+}
+
+fn problem(course_manager: &CourseManager) -> ScheduleProblem {
+    ScheduleProblem::from_course_manager(course_manager, Default::default(), &[])
+}
+
+fn group(code: &str, record_type: CourseRecordType, group: i32) -> SavedGroup {
+    SavedGroup {
+        code: code.to_owned(),
+        record_type,
+        group,
+    }
+}
+
+#[test]
+fn sessions_of_a_group_share_a_bundle() {
+    let course_manager = catalogue();
+    let problem = problem(&course_manager);
+
+    let lectures = &problem.slots[0];
+    assert_eq!(
+        (lectures.course_code.as_str(), lectures.record_type),
+        ("CSE111", CourseRecordType::Lecture)
+    );
+    assert_eq!(lectures.bundles.len(), 2);
+    assert_eq!(lectures.bundles[0].group, 1);
+    assert_eq!(lectures.bundles[0].record_indices, [0, 1]);
+    assert_eq!(lectures.bundles[0].sessions.len(), 2);
+}
+
+#[test]
+fn every_course_gets_one_lecture_and_one_tutorial() {
+    use CourseRecordType::{Lecture, Tutorial};
+
+    let course_manager = catalogue();
+    let problem = problem(&course_manager);
+    assert_eq!(problem.slots.len(), 4);
+
+    let schedules = problem.generate(usize::MAX);
+    let groups: Vec<Vec<SavedGroup>> = schedules
+        .iter()
+        .map(|schedule| schedule.saved_groups(&problem))
+        .collect();
+
+    assert_eq!(
+        groups,
+        [
+            vec![
+                group("CSE111", Lecture, 2),
+                group("CSE111", Tutorial, 1),
+                group("MTH101", Lecture, 1),
+                group("MTH101", Tutorial, 1),
+            ],
+            vec![
+                group("CSE111", Lecture, 2),
+                group("CSE111", Tutorial, 2),
+                group("MTH101", Lecture, 1),
+                group("MTH101", Tutorial, 1),
+            ],
+        ]
+    );
+
+    // Lecture G1 goes as a whole, its Sunday session doesnt make it in on its own
+    assert!(
+        schedules
+            .iter()
+            .all(|schedule| !schedule.record_indices(&problem).contains(&0))
+    );
+}
+
+#[test]
+fn unselected_courses_are_left_out() {
+    let course_manager = catalogue();
+    let mth101 = course_manager
+        .course_definitions
+        .iter()
+        .find(|def| def.borrow().code == "MTH101")
+        .map(Rc::clone)
+        .unwrap();
+    mth101.borrow_mut().selected = false;
+
+    let problem = problem(&course_manager);
+
+    assert_eq!(problem.slots.len(), 2);
+    // Nothing clashes with lecture G1 anymore
+    assert_eq!(problem.generate(usize::MAX).len(), 4);
+}
+
+#[test]
+fn generation_stops_at_the_cap() {
+    let course_manager = catalogue();
+    let problem = problem(&course_manager);
+    let every_schedule = problem.generate(usize::MAX);
+
+    assert_eq!(problem.generate(1), every_schedule[..1]);
+    assert!(problem.generate(0).is_empty());
+}
@@ -0,0 +1,168 @@
+mod common;
+
+use chrono::Weekday;
+use common::{RecordBuilder, time};
+use cryn_rs::{
+    models::{CampusMap, SchedulePreferences},
+    services::{CourseManager, Schedule, ScheduleProblem, rank_schedules, score_schedule},
+};
+
+/// CSE111 G1 early on Sunday or G2 on Monday, MTH101 G1 Sunday afternoon or G2 Sunday morning
+/// Schedules come out as (G1, G1), (G1, G2), (G2, G1), (G2, G2)
+fn problem() -> ScheduleProblem {
+    let mut course_manager = CourseManager::new();
+    RecordBuilder::new("CSE111")
+        .times(time(8, 0), time(9, 50))
+        .add_to(&mut course_manager);
+    RecordBuilder::new("CSE111")
+        .group(2)
+        .day(Weekday::Mon)
+        .times(time(10, 0), time(11, 50))
+        .add_to(&mut course_manager);
+    RecordBuilder::new("MTH101")
+        .times(time(13, 0), time(14, 50))
+        .add_to(&mut course_manager);
+    RecordBuilder::new("MTH101")
+        .group(2)
+        .times(time(10, 30), time(12, 20))
+        .add_to(&mut course_manager);
+
+    ScheduleProblem::from_course_manager(&course_manager, Default::default(), &[])
+}
+
+fn schedule(cse111: usize, mth101: usize) -> Schedule {
+    Schedule {
+        choices: vec![cse111, mth101],
+    }
+}
+
+#[test]
+fn scores_count_days_early_sessions_gaps_and_campus_time() {
+    let problem = problem();
+    let preferences = SchedulePreferences::default();
+    let score = |schedule| score_schedule(&problem, &schedule, &preferences, &CampusMap::bundled());
+
+    // Both on Sunday, 8:00 is before the default 9:00 start
+    let spread_out = score(schedule(0, 0));
+    assert_eq!(spread_out.campus_days, 1);
+    assert_eq!(spread_out.early_sessions, 1);
+    assert_eq!(spread_out.gap_minutes, 190);
+    assert_eq!(spread_out.campus_minutes, 410);
+    assert!(!spread_out.free_day_taken);
+    assert_eq!(spread_out.tight_transfers, 0);
+
+    let compact = score(schedule(0, 1));
+    assert_eq!(compact.campus_days, 1);
+    assert_eq!(compact.gap_minutes, 40);
+    assert_eq!(compact.campus_minutes, 260);
+
+    // One session on each day, nothing to wait for in between
+    let two_days = score(schedule(1, 0));
+    assert_eq!(two_days.campus_days, 2);
+    assert_eq!(two_days.early_sessions, 0);
+    assert_eq!(two_days.gap_minutes, 0);
+    assert_eq!(two_days.campus_minutes, 220);
+
+    // 10 per day, 5 per early session, 2 per gap hour, 0.5 per campus hour
+    assert!((compact.penalty - 18.5).abs() < 1e-4);
+    assert!((spread_out.penalty - 24.75).abs() < 1e-4);
+}
+
+#[test]
+fn taking_the_free_day_costs_its_weight() {
+    let problem = problem();
+    let preferences = SchedulePreferences {
+        free_day: Some(Weekday::Mon),
+        ..Default::default()
+    };
+    let campus_map = CampusMap::bundled();
+
+    let without = score_schedule(
+        &problem,
+        &schedule(1, 0),
+        &SchedulePreferences::default(),
+        &campus_map,
+    );
+    let with = score_schedule(&problem, &schedule(1, 0), &preferences, &campus_map);
+
+    assert!(with.free_day_taken);
+    assert!((with.penalty - without.penalty - preferences.free_day_weight).abs() < 1e-4);
+    assert!(!score_schedule(&problem, &schedule(0, 1), &preferences, &campus_map).free_day_taken);
+}
+
+fn ranked(preferences: &SchedulePreferences) -> Vec<Schedule> {
+    let problem = problem();
+    let mut schedules = problem.generate(usize::MAX);
+    assert_eq!(
+        schedules,
+        [
+            schedule(0, 0),
+            schedule(0, 1),
+            schedule(1, 0),
+            schedule(1, 1)
+        ]
+    );
+
+    rank_schedules(&problem, &mut schedules, preferences, &CampusMap::bundled());
+    schedules
+}
+
+#[test]
+fn ranking_follows_the_weights() {
+    // Fewer days wins by default, ties keep their generation order
+    assert_eq!(
+        ranked(&SchedulePreferences::default()),
+        [
+            schedule(0, 1),
+            schedule(1, 0),
+            schedule(1, 1),
+            schedule(0, 0)
+        ]
+    );
+
+    // Monday off pushes both Monday schedules last
+    assert_eq!(
+        ranked(&SchedulePreferences {
+            free_day: Some(Weekday::Mon),
+            ..Default::default()
+        }),
+        [
+            schedule(0, 1),
+            schedule(0, 0),
+            schedule(1, 0),
+            schedule(1, 1)
+        ]
+    );
+
+    // Only caring about days on campus
+    assert_eq!(
+        ranked(&SchedulePreferences {
+            early_start_weight: 0.0,
+            gaps_weight: 0.0,
+            compactness_weight: 0.0,
+            ..Default::default()
+        }),
+        [
+            schedule(0, 0),
+            schedule(0, 1),
+            schedule(1, 0),
+            schedule(1, 1)
+        ]
+    );
+
+    // Only caring about early starts
+    assert_eq!(
+        ranked(&SchedulePreferences {
+            campus_days_weight: 0.0,
+            gaps_weight: 0.0,
+            compactness_weight: 0.0,
+            ..Default::default()
+        }),
+        [
+            schedule(1, 0),
+            schedule(1, 1),
+            schedule(0, 0),
+            schedule(0, 1)
+        ]
+    );
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use common::{RecordBuilder, offered_groups};
+use cryn_rs::{
+    models::{Availability, GroupFilter, RecordStatus},
+    services::CourseManager,
+};
+
+#[test]
+fn status_text_is_typed() {
+    assert_eq!(RecordStatus::parse("Opened"), RecordStatus::Open);
+    assert_eq!(RecordStatus::parse(" CLOSED "), RecordStatus::Closed);
+    assert_eq!(RecordStatus::parse(""), RecordStatus::Unknown);
+    assert_eq!(
+        RecordStatus::parse("Cancelled"),
+        RecordStatus::Other("Cancelled".to_owned())
+    );
+}
+
+#[test]
+fn availability_follows_status_and_seats() {
+    let open = RecordStatus::Open;
+
+    assert_eq!(
+        Availability::new(&open, 30, 25, 0),
+        Availability::Open { seats_left: 5 }
+    );
+    assert_eq!(
+        Availability::new(&open, 30, 30, 4),
+        Availability::Waitlisted { waiting: 4 }
+    );
+    assert_eq!(
+        Availability::new(&RecordStatus::Closed, 30, 2, 0),
+        Availability::Closed
+    );
+
+    // Portal rows with a class size of 0
+    assert_eq!(
+        Availability::new(&open, 0, 14, 14),
+        Availability::UnknownCapacity
+    );
+}
+
+/// One course, lecture groups 1 (closed), 2 (full) and 3 (open)
+fn course_manager() -> CourseManager {
+    let mut course_manager = CourseManager::new();
+    let groups = [
+        (1, RecordStatus::Closed, 10),
+        (2, RecordStatus::Open, 30),
+        (3, RecordStatus::Open, 20),
+    ];
+    for (group, status, enrolled) in groups {
+        RecordBuilder::new("CSE111")
+            .group(group)
+            .seats(30, enrolled, 0)
+            .status(status)
+            .add_to(&mut course_manager);
+    }
+
+    course_manager
+}
+
+#[test]
+fn group_filter_leaves_out_unavailable_groups() {
+    assert_eq!(
+        offered_groups(&course_manager(), GroupFilter::default(), &[]),
+        [1, 2, 3]
+    );
+    assert_eq!(
+        offered_groups(
+            &course_manager(),
+            GroupFilter {
+                skip_closed: true,
+                skip_full: false,
+            },
+            &[]
+        ),
+        [2, 3]
+    );
+    assert_eq!(
+        offered_groups(
+            &course_manager(),
+            GroupFilter {
+                skip_closed: true,
+                skip_full: true,
+            },
+            &[]
+        ),
+        [3]
+    );
+}
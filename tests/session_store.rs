@@ -0,0 +1,86 @@
+mod common;
+
+use common::RecordBuilder;
+use cryn_rs::{
+    models::{CourseRecordType, Location, SavedSelection, SavedSession},
+    services::{CourseManager, dataset_hash},
+};
+
+/// CSE111 lecture G1 and G2 plus a tutorial, all in `room` with `enrolled` students
+fn term(enrolled: i32, room: &str) -> CourseManager {
+    let mut course_manager = CourseManager::new();
+    for (group, record_type) in [
+        (1, CourseRecordType::Lecture),
+        (2, CourseRecordType::Lecture),
+        (1, CourseRecordType::Tutorial),
+    ] {
+        RecordBuilder::new("CSE111")
+            .group(group)
+            .record_type(record_type)
+            .seats(30, enrolled, 0)
+            .location(Location::parse(room))
+            .add_to(&mut course_manager);
+    }
+
+    course_manager
+}
+
+#[test]
+fn hash_ignores_seats_and_rooms() {
+    let hash = dataset_hash(&term(0, "B12"));
+
+    assert_eq!(hash.len(), 16);
+    assert_eq!(dataset_hash(&term(25, "C204")), hash);
+}
+
+#[test]
+fn hash_ignores_record_order() {
+    let mut course_manager = term(0, "B12");
+    let hash = dataset_hash(&course_manager);
+
+    course_manager.course_records.reverse();
+    assert_eq!(dataset_hash(&course_manager), hash);
+}
+
+#[test]
+fn hash_changes_with_the_offered_groups() {
+    let mut course_manager = term(0, "B12");
+    let hash = dataset_hash(&course_manager);
+
+    RecordBuilder::new("CSE111")
+        .group(3)
+        .add_to(&mut course_manager);
+    assert_ne!(dataset_hash(&course_manager), hash);
+}
+
+fn selection(last_used: i64) -> SavedSelection {
+    SavedSelection {
+        last_used,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn stalest_selection_gets_evicted() {
+    let mut session = SavedSession::default();
+    for last_used in (0..=SavedSession::MAX_SELECTIONS as i64).rev() {
+        session.insert_selection(format!("term{last_used}"), selection(last_used));
+    }
+
+    assert_eq!(session.selections.len(), SavedSession::MAX_SELECTIONS);
+    assert!(!session.selections.contains_key("term0"));
+    assert!(session.selections.contains_key("term1"));
+}
+
+#[test]
+fn updating_a_selection_evicts_nothing() {
+    let mut session = SavedSession::default();
+    for last_used in 0..SavedSession::MAX_SELECTIONS as i64 {
+        session.insert_selection(format!("term{last_used}"), selection(last_used));
+    }
+
+    session.insert_selection("term0".to_owned(), selection(100));
+
+    assert_eq!(session.selections.len(), SavedSession::MAX_SELECTIONS);
+    assert_eq!(session.selections["term0"].last_used, 100);
+}
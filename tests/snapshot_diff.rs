@@ -0,0 +1,205 @@
+use chrono::Weekday;
+use cryn_rs::{
+    models::CourseRecordType,
+    services::{
+        CourseManager, ParseInput, ParseOptions, ParserRegistry, PortalParser, RecordChange,
+        diff_snapshots, is_same_dataset,
+    },
+};
+
+const HEADER: &str = "<tr><th>id</th><th>Code</th><th>Name</th><th>Group</th><th>Type</th>\
+    <th>Day</th><th>From</th><th>To</th><th>Class Size</th><th>Enrolled</th><th>Waiting</th>\
+    <th>Status</th><th>Location</th><th>Date</th></tr>";
+
+/// Rows are id|code|group|type|day|from|to|enrolled|waiting|status|location
+fn snapshot(date: &str, rows: &[&str]) -> CourseManager {
+    let mut html = format!("<table>{}", HEADER);
+    for row in rows {
+        let cells: Vec<&str> = row.split('|').collect();
+        let [
+            id,
+            code,
+            group,
+            record_type,
+            day,
+            from,
+            to,
+            enrolled,
+            waiting,
+            status,
+            location,
+        ] = cells[..]
+        else {
+            panic!("Bad test row {}", row);
+        };
+
+        html.push_str(&format!(
+            "<tr><td>{id}</td><td>__{code}__</td><td>{code} name</td><td>{group}</td>\
+             <td>{record_type}</td><td>{day}</td><td>{from}</td><td>{to}</td><td>30</td>\
+             <td>{enrolled}</td><td>{waiting}</td><td>{status}</td><td>{location}</td>\
+             <td>{date}</td></tr>"
+        ));
+    }
+    html.push_str("</table>");
+
+    CourseManager::stage_courses_as(
+        &ParserRegistry::default(),
+        PortalParser::ID,
+        &ParseInput::from_text("test", &html),
+        &ParseOptions::default(),
+    )
+    .unwrap()
+}
+
+fn older() -> CourseManager {
+    snapshot(
+        "1/21/2026 5:30:00 PM",
+        &[
+            "1|CSE111|1|Lecture|Sunday|9:00|10:50|20|0|Opened|H1",
+            "2|CSE111|2|Lecture|Monday|9:00|10:50|29|0|Opened|H1",
+            "3|MTH101|1|Tutorial|Tuesday|11:00|12:50|10|0|Opened|B2",
+        ],
+    )
+}
+
+#[test]
+fn identical_snapshots_have_no_changes() {
+    assert!(diff_snapshots(&older(), &older()).is_empty());
+}
+
+#[test]
+fn reports_every_kind_of_change() {
+    let newer = snapshot(
+        "1/21/2026 6:30:00 PM",
+        &[
+            // Moved to another room and time
+            "1|CSE111|1|Lecture|Sunday|11:00|12:50|20|0|Opened|H2",
+            // Filled up
+            "2|CSE111|2|Lecture|Monday|9:00|10:50|30|2|Closed|H1",
+            // New group, 3 was dropped
+            "4|MTH101|2|Tutorial|Tuesday|1:00|2:50|0|0|Opened|B2",
+        ],
+    );
+
+    let diff = diff_snapshots(&older(), &newer);
+    assert!(diff.old_snapshot_time < diff.new_snapshot_time);
+
+    let changes: Vec<(&str, CourseRecordType, i32, &[RecordChange])> = diff
+        .records
+        .iter()
+        .map(|record| {
+            (
+                record.code.as_str(),
+                record.record_type,
+                record.group,
+                record.changes.as_slice(),
+            )
+        })
+        .collect();
+
+    assert_eq!(changes.len(), 4);
+    assert!(matches!(
+        changes[0],
+        (
+            "CSE111",
+            CourseRecordType::Lecture,
+            1,
+            [
+                RecordChange::Rescheduled { .. },
+                RecordChange::Relocated { .. }
+            ]
+        )
+    ));
+    assert!(matches!(
+        changes[1],
+        (
+            "CSE111",
+            CourseRecordType::Lecture,
+            2,
+            [
+                RecordChange::StatusChanged { .. },
+                RecordChange::SeatsChanged {
+                    enrolled: 1,
+                    waiting: 2
+                }
+            ]
+        )
+    ));
+    assert!(matches!(
+        changes[2],
+        ("MTH101", _, 1, [RecordChange::Removed])
+    ));
+    assert!(matches!(
+        changes[3],
+        ("MTH101", _, 2, [RecordChange::Added])
+    ));
+
+    let selected = diff.for_courses(&["MTH101".to_owned()]);
+    assert_eq!(selected.len(), 2);
+}
+
+#[test]
+fn only_snapshots_of_the_same_courses_are_comparable() {
+    let newer = snapshot(
+        "1/22/2026 9:00:00 AM",
+        &[
+            "1|CSE111|1|Lecture|Sunday|9:00|10:50|25|0|Opened|H1",
+            "3|MTH101|1|Tutorial|Tuesday|11:00|12:50|12|0|Opened|B2",
+            "4|PHY101|1|Lecture|Monday|13:00|14:50|5|0|Opened|C3",
+        ],
+    );
+    let other_term = snapshot(
+        "6/1/2026 9:00:00 AM",
+        &[
+            "7|ENG201|1|Lecture|Sunday|9:00|10:50|0|0|Opened|H1",
+            "8|PHY101|1|Lecture|Monday|13:00|14:50|0|0|Opened|C3",
+        ],
+    );
+
+    assert!(is_same_dataset(&older(), &newer));
+    assert!(!is_same_dataset(&older(), &other_term));
+    assert!(!is_same_dataset(&CourseManager::new(), &older()));
+}
+
+#[test]
+fn records_sharing_a_slot_are_matched_in_order() {
+    // No ids, both halves of the split group have the same slot key
+    let split = |second_room: &str| {
+        snapshot(
+            "1/21/2026 5:30:00 PM",
+            &[
+                "|CSE111|1|Lecture|Sunday|9:00|10:50|20|0|Opened|H1",
+                &format!("|CSE111|1|Lecture|Sunday|9:00|10:50|20|0|Opened|{second_room}"),
+            ],
+        )
+    };
+
+    assert!(diff_snapshots(&split("H2"), &split("H2")).is_empty());
+
+    let diff = diff_snapshots(&split("H2"), &split("H3"));
+    assert_eq!(diff.records.len(), 1);
+    assert!(matches!(
+        diff.records[0].changes.as_slice(),
+        [RecordChange::Relocated { .. }]
+    ));
+}
+
+#[test]
+fn days_are_listed_saturday_first() {
+    let week = |enrolled: i32| {
+        snapshot(
+            "1/21/2026 5:30:00 PM",
+            &[
+                &format!("1|CSE111|1|Lecture|Monday|9:00|10:50|{enrolled}|0|Opened|H1"),
+                &format!("2|CSE111|1|Lecture|Saturday|9:00|10:50|{enrolled}|0|Opened|H1"),
+            ],
+        )
+    };
+
+    let days: Vec<Weekday> = diff_snapshots(&week(10), &week(20))
+        .records
+        .iter()
+        .map(|record| record.meeting.day)
+        .collect();
+    assert_eq!(days, [Weekday::Sat, Weekday::Mon]);
+}
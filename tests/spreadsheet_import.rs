@@ -0,0 +1,120 @@
+use cryn_rs::{
+    models::CourseParseFormat,
+    services::{CourseColumn, CourseManager, ParseOptions, SpreadsheetMapping, Workbook},
+};
+
+const FACULTY_TIMETABLE: &[u8] = include_bytes!("fixtures/faculty_timetable.xlsx");
+
+fn timetable_mapping(workbook: &Workbook) -> SpreadsheetMapping {
+    // Title on the first row, headers on the third
+    let headers = workbook.rows("Timetable").unwrap()[2].clone();
+    SpreadsheetMapping::guess("Timetable", 2, &headers)
+}
+
+#[test]
+fn guesses_columns_from_headers() {
+    let workbook = Workbook::read(FACULTY_TIMETABLE).unwrap();
+    let mapping = timetable_mapping(&workbook);
+
+    assert!(mapping.missing_columns().is_empty());
+    assert_eq!(mapping.columns[&CourseColumn::Code], 0);
+    assert_eq!(mapping.columns[&CourseColumn::Name], 1);
+    assert_eq!(mapping.columns[&CourseColumn::Group], 2);
+    assert_eq!(mapping.columns[&CourseColumn::From], 5);
+    assert_eq!(mapping.columns[&CourseColumn::Location], 8);
+}
+
+#[test]
+fn imports_mapped_rows_as_excel_records() {
+    let workbook = Workbook::read(FACULTY_TIMETABLE).unwrap();
+    let staged = CourseManager::stage_spreadsheet(
+        &workbook,
+        &timetable_mapping(&workbook),
+        &ParseOptions::default(),
+    )
+    .unwrap();
+
+    // The seminar row has no usable type
+    assert_eq!(staged.parse_report.imported, 4);
+    assert_eq!(staged.parse_report.skipped(), 1);
+    assert_eq!(staged.course_definitions.len(), 2);
+
+    let records: Vec<String> = staged
+        .course_records
+        .iter()
+        .map(|record| {
+            let record = record.borrow();
+            assert!(matches!(record.parse_format, CourseParseFormat::Excel));
+            format!(
+                "{} {:?} G{} {} {}-{} {} {}",
+                record.course_definition.borrow().code,
+                record.record_type,
+                record.group,
+                record.day,
+                record.start_time.format("%H:%M"),
+                record.end_time.format("%H:%M"),
+                record.class_size,
+                record.location
+            )
+        })
+        .collect();
+
+    // 1:00 - 3:00 is an afternoon lecture
+    assert_eq!(
+        records,
+        [
+            "CSE111 Lecture G1 Sat 09:00-11:00 120 Hall 5",
+            "PHY101 Lecture G1 Sun 13:00-15:00 90 B2-101",
+            "CSE111 Tutorial G2 Mon 13:30-15:00 30 Lab 3",
+            "PHY101 Tutorial G3 Wed 11:00-12:30 25 B2-204",
+        ]
+    );
+}
+
+#[test]
+fn reports_unmapped_required_columns() {
+    let workbook = Workbook::read(FACULTY_TIMETABLE).unwrap();
+    let mut mapping = timetable_mapping(&workbook);
+    mapping.columns.remove(&CourseColumn::Day);
+
+    assert_eq!(mapping.missing_columns(), [CourseColumn::Day]);
+    assert!(
+        CourseManager::stage_spreadsheet(&workbook, &mapping, &ParseOptions::default()).is_err()
+    );
+}
+
+fn guess(headers: &[&str]) -> SpreadsheetMapping {
+    let headers: Vec<String> = headers.iter().map(|header| header.to_string()).collect();
+    SpreadsheetMapping::guess("Sheet1", 0, &headers)
+}
+
+#[test]
+fn guesses_whole_titles_only() {
+    let mapping = guess(&[
+        "Room Name",
+        "Instructor Name",
+        "Attendance",
+        "Calendar",
+        "Postcode",
+        "Weekend",
+    ]);
+
+    assert!(mapping.columns.is_empty());
+}
+
+#[test]
+fn guessing_ignores_case_and_punctuation() {
+    let mapping = guess(&[
+        "Instructor Name",
+        "COURSE  CODE",
+        "Course-Title",
+        "Group #",
+        "End:",
+    ]);
+
+    assert_eq!(mapping.columns.len(), 4);
+    assert_eq!(mapping.columns[&CourseColumn::Code], 1);
+    assert_eq!(mapping.columns[&CourseColumn::Name], 2);
+    assert_eq!(mapping.columns[&CourseColumn::Group], 3);
+    assert_eq!(mapping.columns[&CourseColumn::To], 4);
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use common::RecordBuilder;
+use cryn_rs::{
+    models::{CampusMap, CourseRecordType, Location, Route, SchedulePreferences},
+    services::{CourseManager, ScheduleProblem, find_tight_transfers, score_schedule},
+};
+use std::rc::Rc;
+
+const GIZA: &str = "الجيزة الرئيسي";
+const ZAYED: &str = "الشيخ زايد";
+
+fn campus_map() -> CampusMap {
+    CampusMap {
+        campus_minutes: 30,
+        building_minutes: 0,
+        routes: vec![
+            Route {
+                between: [GIZA.to_owned(), ZAYED.to_owned()],
+                minutes: 90,
+            },
+            Route {
+                between: ["20".to_owned(), "40".to_owned()],
+                minutes: 15,
+            },
+        ],
+    }
+}
+
+fn portal_location(room: u32, campus: &str) -> Location {
+    Location::parse(&format!("[{room}]{room}-60-{campus}"))
+}
+
+#[test]
+fn travel_times_follow_the_map() {
+    let campus_map = campus_map();
+    let giza = portal_location(20103, GIZA);
+
+    assert_eq!(campus_map.travel_minutes(&giza, &giza), 0);
+    assert_eq!(
+        campus_map.travel_minutes(&giza, &portal_location(51113, ZAYED)),
+        90
+    );
+    assert_eq!(
+        campus_map.travel_minutes(&portal_location(51113, ZAYED), &giza),
+        90
+    );
+
+    // Unlisted campuses, listed buildings and unlisted buildings
+    assert_eq!(
+        campus_map.travel_minutes(&giza, &portal_location(33310, "ملحق كيمياء")),
+        30
+    );
+    assert_eq!(
+        campus_map.travel_minutes(&giza, &portal_location(40310, "ملحق طيران")),
+        15
+    );
+    assert_eq!(
+        campus_map.travel_minutes(&giza, &portal_location(14501, GIZA)),
+        0
+    );
+
+    // Rooms we know nothing about
+    assert_eq!(campus_map.travel_minutes(&giza, &Location::default()), 0);
+}
+
+#[test]
+fn bundled_map_loads() {
+    let campus_map = CampusMap::bundled();
+    assert!(!campus_map.routes.is_empty());
+    assert_eq!(CampusMap::default(), campus_map);
+}
+
+/// CSE111 lecture at Giza 9:00-10:50, tutorial groups 1 at Zayed and 2 at Giza, both 11:00-12:50
+fn course_manager() -> CourseManager {
+    let mut course_manager = CourseManager::new();
+    let records = [
+        (
+            CourseRecordType::Lecture,
+            1,
+            9,
+            portal_location(20103, GIZA),
+        ),
+        (
+            CourseRecordType::Tutorial,
+            1,
+            11,
+            portal_location(51113, ZAYED),
+        ),
+        (
+            CourseRecordType::Tutorial,
+            2,
+            11,
+            portal_location(20104, GIZA),
+        ),
+    ];
+    for (record_type, group, hour, location) in records {
+        RecordBuilder::new("CSE111")
+            .group(group)
+            .record_type(record_type)
+            .period(hour)
+            .location(location)
+            .add_to(&mut course_manager);
+    }
+
+    course_manager
+}
+
+#[test]
+fn back_to_back_campus_changes_are_flagged() {
+    let course_manager = course_manager();
+    let transfers = find_tight_transfers(&course_manager.course_records, &campus_map());
+
+    assert_eq!(transfers.len(), 1);
+    let transfer = &transfers[0];
+    assert!(Rc::ptr_eq(
+        &transfer.from,
+        &course_manager.course_records[0]
+    ));
+    assert!(Rc::ptr_eq(&transfer.to, &course_manager.course_records[1]));
+    assert_eq!(transfer.break_minutes, 10);
+    assert_eq!(transfer.needed_minutes, 90);
+}
+
+#[test]
+fn rushed_schedules_score_worse() {
+    let course_manager = course_manager();
+    let problem = ScheduleProblem::from_course_manager(&course_manager, Default::default(), &[]);
+    let preferences = SchedulePreferences::default();
+
+    let schedules = problem.generate(10);
+    assert_eq!(schedules.len(), 2);
+
+    let scores: Vec<_> = schedules
+        .iter()
+        .map(|schedule| score_schedule(&problem, schedule, &preferences, &campus_map()))
+        .collect();
+
+    // Tutorial slot comes second, group 1 is the one at Zayed
+    let tutorial_group = |idx: usize| problem.slots[1].bundles[schedules[idx].choices[1]].group;
+    for (idx, score) in scores.iter().enumerate() {
+        let expected = if tutorial_group(idx) == 1 { 1 } else { 0 };
+        assert_eq!(score.tight_transfers, expected);
+    }
+
+    let rushed = scores
+        .iter()
+        .find(|score| score.tight_transfers == 1)
+        .unwrap();
+    let relaxed = scores
+        .iter()
+        .find(|score| score.tight_transfers == 0)
+        .unwrap();
+    assert_eq!(
+        rushed.penalty - relaxed.penalty,
+        preferences.tight_transfer_weight
+    );
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use chrono::{NaiveTime, Weekday};
+use common::{RecordBuilder, time};
+use cryn_rs::{
+    models::WeekOccupancy,
+    services::{CourseManager, ScheduleProblem},
+};
+
+fn occupancy(ranges: &[(Weekday, NaiveTime, NaiveTime)]) -> WeekOccupancy {
+    let mut occupancy = WeekOccupancy::new();
+    for &(day, start_time, end_time) in ranges {
+        occupancy.insert(day, start_time, end_time);
+    }
+
+    occupancy
+}
+
+#[test]
+fn touching_ranges_dont_overlap() {
+    let morning = occupancy(&[(Weekday::Sun, time(9, 0), time(10, 50))]);
+    let next = occupancy(&[(Weekday::Sun, time(10, 50), time(12, 40))]);
+    let inside = occupancy(&[(Weekday::Sun, time(10, 45), time(11, 0))]);
+    let other_day = occupancy(&[(Weekday::Mon, time(9, 0), time(10, 50))]);
+
+    assert!(!morning.overlaps(&next));
+    assert!(morning.overlaps(&inside));
+    assert!(!morning.overlaps(&other_day));
+    assert!(!morning.is_approximate());
+}
+
+#[test]
+fn union_overlaps_like_its_parts() {
+    let mut picked = occupancy(&[(Weekday::Sun, time(9, 0), time(10, 50))]);
+    picked.union_with(&occupancy(&[(Weekday::Tue, time(23, 0), time(23, 59))]));
+
+    assert!(picked.overlaps(&occupancy(&[(Weekday::Tue, time(23, 55), time(23, 59))])));
+    assert!(!picked.overlaps(&occupancy(&[(Weekday::Tue, time(22, 0), time(23, 0))])));
+}
+
+#[test]
+fn off_grid_times_are_rounded_outwards() {
+    let early = occupancy(&[(Weekday::Sun, time(9, 0), time(10, 2))]);
+    let late = occupancy(&[(Weekday::Sun, time(10, 3), time(11, 0))]);
+
+    // Both land in the 10:00 slot
+    assert!(early.is_approximate());
+    assert!(early.overlaps(&late));
+}
+
+/// Off grid times touching in the same slot shouldnt clash in generated schedules
+#[test]
+fn generator_checks_rounded_times_exactly() {
+    let mut course_manager = CourseManager::new();
+    let rows = [
+        ("CSE111", time(9, 0), time(10, 2)),
+        ("MTH101", time(10, 3), time(11, 0)),
+        ("PHY101", time(10, 0), time(10, 30)),
+    ];
+
+    for (code, start_time, end_time) in rows {
+        RecordBuilder::new(code)
+            .times(start_time, end_time)
+            .add_to(&mut course_manager);
+    }
+
+    // CSE111 and MTH101 fit together, PHY101 clashes with both
+    let problem = ScheduleProblem::from_indexed_records(
+        course_manager.course_records[..2].iter().enumerate(),
+    );
+    assert_eq!(problem.generate(10).len(), 1);
+
+    let problem =
+        ScheduleProblem::from_indexed_records(course_manager.course_records.iter().enumerate());
+    assert!(problem.generate(10).is_empty());
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use chrono::{NaiveDate, Weekday};
+use common::time;
+use cryn_rs::models::{OrderedWeekday, TimeFormat, format_date_time};
+use std::collections::BTreeSet;
+
+#[test]
+fn weekdays_sort_saturday_first() {
+    let days: BTreeSet<OrderedWeekday> = [Weekday::Fri, Weekday::Mon, Weekday::Sat, Weekday::Sun]
+        .into_iter()
+        .map(OrderedWeekday::from)
+        .collect();
+
+    let days: Vec<Weekday> = days.iter().map(OrderedWeekday::weekday).collect();
+    assert_eq!(
+        days,
+        [Weekday::Sat, Weekday::Sun, Weekday::Mon, Weekday::Fri]
+    );
+}
+
+#[test]
+fn days_since_follows_the_week_start() {
+    let monday = OrderedWeekday::from(Weekday::Mon);
+
+    assert_eq!(monday.days_since(Weekday::Sat), 2);
+    assert_eq!(monday.days_since(Weekday::Mon), 0);
+    assert_eq!(monday.days_since(Weekday::Tue), 6);
+}
+
+#[test]
+fn week_starts_at_the_week_start() {
+    let week: Vec<Weekday> = OrderedWeekday::week(Weekday::Mon).collect();
+
+    assert_eq!(
+        week,
+        [
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+            Weekday::Sat,
+            Weekday::Sun
+        ]
+    );
+    assert_eq!(OrderedWeekday::from(week[3]).to_string(), "Thursday");
+}
+
+#[test]
+fn times_follow_the_format() {
+    assert_eq!(TimeFormat::TwentyFourHour.format(time(9, 5)), "09:05");
+    assert_eq!(TimeFormat::TwentyFourHour.format(time(13, 30)), "13:30");
+    assert_eq!(TimeFormat::TwelveHour.format(time(9, 5)), "9:05 AM");
+    assert_eq!(TimeFormat::TwelveHour.format(time(13, 30)), "1:30 PM");
+}
+
+#[test]
+fn hours_follow_the_format() {
+    assert_eq!(TimeFormat::TwentyFourHour.format_hour(8), "8:00");
+    assert_eq!(TimeFormat::TwentyFourHour.format_hour(17), "17:00");
+    assert_eq!(TimeFormat::TwelveHour.format_hour(0), "12 AM");
+    assert_eq!(TimeFormat::TwelveHour.format_hour(12), "12 PM");
+    assert_eq!(TimeFormat::TwelveHour.format_hour(17), "5 PM");
+}
+
+#[test]
+fn date_times_follow_the_format() {
+    let date_time = NaiveDate::from_ymd_opt(2026, 1, 21)
+        .unwrap()
+        .and_time(time(17, 30));
+
+    assert_eq!(
+        format_date_time(date_time, TimeFormat::TwentyFourHour),
+        "21 Jan 2026 17:30"
+    );
+    assert_eq!(
+        format_date_time(date_time, TimeFormat::TwelveHour),
+        "21 Jan 2026 5:30 PM"
+    );
+}